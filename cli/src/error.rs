@@ -11,6 +11,12 @@ pub enum Error {
     ReadingFile(io::Error),
     /// Error when serializing output
     Serializing(String),
+    /// Error while writing a formatted file back to disk
+    WritingFile(io::Error),
+    /// Files which are not formatted when running with `--check`
+    Unformatted(Vec<String>),
+    /// The number of warnings produced when running with `--deny-warnings`
+    DeniedWarnings(usize),
 }
 
 pub trait ExitCode {
@@ -22,11 +28,21 @@ impl ExitCode for CornError {
         match self {
             CornError::Io(_) => 3,
             CornError::ParserError(_) => 1,
-            CornError::InputResolveError(_) => 2,
-            CornError::InvalidPathError(_) => 6,
-            CornError::InvalidSpreadError(_) => 7,
-            CornError::InvalidInterpolationError(_) => 8,
-            CornError::DeserializationError(_) => 5,
+            CornError::InputResolveError(..) => 2,
+            CornError::InvalidPathError(..) => 6,
+            CornError::DuplicateKeyError(..) => 16,
+            CornError::InvalidSpreadError(..) => 7,
+            CornError::InvalidInterpolationError(..) => 8,
+            CornError::ImportError(..) => 11,
+            CornError::ImportCycleError(..) => 12,
+            CornError::EnvAccessDeniedError(..) => 13,
+            CornError::InvalidCastError(..) => 14,
+            CornError::RequiredInputError { .. } => 15,
+            CornError::IntegerOverflowError(..) => 18,
+            CornError::FloatOverflowError(..) => 19,
+            CornError::DeserializationError { .. } => 5,
+            CornError::SerializationError(_) => 4,
+            CornError::EditError(_) => 9,
         }
     }
 }
@@ -37,6 +53,9 @@ impl ExitCode for Error {
             Error::Corn(err) => err.get_exit_code(),
             Error::ReadingFile(_) => 3,
             Error::Serializing(_) => 4,
+            Error::WritingFile(_) => 3,
+            Error::Unformatted(_) => 10,
+            Error::DeniedWarnings(_) => 17,
         }
     }
 }
@@ -76,6 +95,16 @@ impl Display for Error {
                 f,
                 "The input could not be serialized into the requested output format:\n\t{err}"
             ),
+            Error::WritingFile(err) => write!(f, "{err}"),
+            Error::Unformatted(files) => write!(
+                f,
+                "The following inputs are not formatted:\n\t{}",
+                files.join("\n\t")
+            ),
+            Error::DeniedWarnings(count) => write!(
+                f,
+                "Parsing the input produced {count} warning(s), which are denied by `--deny-warnings`"
+            ),
         }
     }
 }
@@ -0,0 +1,74 @@
+use crate::error::Error;
+use crate::{get_input, print_corn_err, print_error, print_read_err};
+use std::fs;
+use std::process::exit;
+
+#[derive(clap::Args, Debug)]
+pub struct FmtArgs {
+    /// Paths to the corn files to format.
+    /// If not set, reads from stdin and writes to stdout instead.
+    files: Vec<String>,
+
+    /// Checks whether the input is already formatted instead of writing it.
+    /// Exits with an error listing any inputs which would change.
+    #[clap(long)]
+    check: bool,
+}
+
+/// Formats each input file in place,
+/// or checks that they are already formatted.
+pub fn run(args: &FmtArgs) {
+    let inputs = if args.files.is_empty() {
+        vec![None]
+    } else {
+        args.files.iter().map(Some).collect()
+    };
+
+    let mut unformatted = vec![];
+    // the exit code of the first input which failed,
+    // so that every input is still reported before exiting
+    let mut failure = None;
+
+    for input in inputs {
+        let unformatted_file = match get_input(input) {
+            Ok(file) => file,
+            Err(err) => {
+                failure = failure.or(Some(print_read_err(err, input)));
+                continue;
+            }
+        };
+
+        let name = input.map_or("stdin", String::as_str);
+
+        let formatted = match corn::format(&unformatted_file) {
+            Ok(formatted) => formatted,
+            Err(err) => {
+                failure = failure.or(Some(print_corn_err(err, name, Some(&unformatted_file))));
+                continue;
+            }
+        };
+
+        if args.check {
+            if formatted != unformatted_file {
+                unformatted.push(name.to_string());
+            }
+        } else if let Some(path) = input {
+            if formatted != unformatted_file {
+                if let Err(err) = fs::write(path, formatted) {
+                    failure = failure.or(Some(print_error(&Error::WritingFile(err))));
+                }
+            }
+        } else {
+            print!("{formatted}");
+        }
+    }
+
+    if !unformatted.is_empty() {
+        let code = print_error(&Error::Unformatted(unformatted));
+        failure = failure.or(Some(code));
+    }
+
+    if let Some(code) = failure {
+        exit(code);
+    }
+}
@@ -1,12 +1,15 @@
-use corn::{parse, Value};
+use corn::error::Error as CornError;
+use corn::{Diagnostic, ParseOptions, Value};
 use std::io::Read;
 use std::process::exit;
 use std::{fs, io};
 
-use clap::{Parser, ValueEnum};
+use clap::{Parser, Subcommand, ValueEnum};
 use colored::Colorize;
 
 mod error;
+mod fmt;
+mod report;
 use error::{print_err, Error, ExitCode};
 
 #[derive(ValueEnum, Clone, Copy, Debug)]
@@ -17,14 +20,29 @@ enum OutputType {
 }
 
 #[derive(Parser, Debug)]
-#[clap(author, version, about, long_about = None)]
+#[clap(author, version, about, long_about = None, args_conflicts_with_subcommands = true)]
 struct Args {
+    #[clap(subcommand)]
+    command: Option<Command>,
+
     /// Path to the input corn file. If not set, reads from stdin instead.
     input: Option<String>,
 
-    /// The file format to output
+    /// The file format to output.
+    /// TOML only supports integers between -2^63 and 2^63 - 1
     #[clap(long = "type", short = 't', value_enum)]
     output_type: Option<OutputType>,
+
+    /// Exits with an error instead of writing the output
+    /// if parsing the input produces any warnings
+    #[clap(long)]
+    deny_warnings: bool,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Rewrites corn files in the canonical style
+    Fmt(fmt::FmtArgs),
 }
 
 /// Reads input from file if given a path, otherwise stdin
@@ -41,35 +59,89 @@ fn get_input(input: Option<&String>) -> io::Result<String> {
 fn main() {
     let args = Args::parse();
 
-    let unparsed_file = get_input(args.input.as_ref());
-
-    match unparsed_file {
-        Ok(unparsed_file) => {
-            let output_type = get_output_type(args.output_type);
+    if let Some(Command::Fmt(fmt_args)) = args.command {
+        fmt::run(&fmt_args);
+        return;
+    }
 
-            match parse(&unparsed_file) {
-                Ok(config) => match serialize(&config, output_type) {
-                    Ok(serialized) => println!("{serialized}"),
-                    Err(err) => handle_err(&err),
-                },
-                Err(err) => handle_err(&Error::Corn(err)),
-            };
+    let output_type = get_output_type(args.output_type);
+
+    let options = ParseOptions::new();
+
+    // files are parsed from their path so that they can import other files
+    let (config, warnings, source) = if let Some(input) = args.input.as_ref() {
+        match options.parse_file_with_warnings(input) {
+            Ok((config, warnings)) => {
+                // the source is only needed to show snippets for the warnings
+                let source = if warnings.is_empty() {
+                    None
+                } else {
+                    fs::read_to_string(input).ok()
+                };
+
+                (config, warnings, source)
+            }
+            Err(CornError::Io(err)) => handle_read_err(err, Some(input)),
+            Err(err) => {
+                let source = fs::read_to_string(input).ok();
+                handle_corn_err(err, input, source.as_deref())
+            }
         }
-        Err(err) => {
-            print_err(
-                &err.to_string(),
-                Some(format!(
-                    "while attempting to read `{}`",
-                    args.input.unwrap_or_else(|| "stdin".to_string()).bold()
-                )),
-            );
-
-            let error = Error::ReadingFile(err);
-            exit(error.get_exit_code());
+    } else {
+        let unparsed_file = get_input(None).unwrap_or_else(|err| handle_read_err(err, None));
+
+        let (config, warnings) = match options.parse_with_warnings(&unparsed_file) {
+            Ok((config, warnings)) => (config.into_owned(), warnings),
+            Err(err) => handle_corn_err(err, "stdin", Some(&unparsed_file)),
+        };
+
+        (config, warnings, Some(unparsed_file))
+    };
+
+    let name = args.input.as_deref().unwrap_or("stdin");
+    print_warnings(&warnings, name, source.as_deref());
+
+    if args.deny_warnings && !warnings.is_empty() {
+        handle_err(&Error::DeniedWarnings(warnings.len()));
+    }
+
+    match serialize(&config, output_type) {
+        Ok(serialized) => println!("{serialized}"),
+        Err(err) => handle_err(&err),
+    }
+}
+
+/// Prints each warning to `stderr`,
+/// as an annotated snippet of `source` if it is available.
+fn print_warnings(warnings: &[Diagnostic], name: &str, source: Option<&str>) {
+    for warning in warnings {
+        match source {
+            Some(source) => report::print_diagnostic(warning, None, name, source),
+            None => eprintln!("{warning}"),
         }
     }
 }
 
+/// Prints an error which occurred while reading the input
+/// and exits with the matching code.
+fn handle_read_err(err: io::Error, input: Option<&String>) -> ! {
+    exit(print_read_err(err, input));
+}
+
+/// Prints an error which occurred while reading the input,
+/// returning the matching exit code.
+fn print_read_err(err: io::Error, input: Option<&String>) -> i32 {
+    print_err(
+        &err.to_string(),
+        Some(format!(
+            "while attempting to read `{}`",
+            input.map_or("stdin", String::as_str).bold()
+        )),
+    );
+
+    Error::ReadingFile(err).get_exit_code()
+}
+
 /// Gets the file type to use for the output.
 /// If the type arg is supplied, this is used.
 /// Otherwise, the output type falls back to JSON as the default.
@@ -85,15 +157,67 @@ fn serialize(config: &Value, output_type: OutputType) -> Result<String, Error> {
     match output_type {
         OutputType::Json => serde_json::to_string_pretty(&config).map_err(Error::from),
         OutputType::Yaml => serde_norway::to_string(&config).map_err(Error::from),
-        OutputType::Toml => toml_edit::ser::to_string_pretty(&config).map_err(Error::from),
+        OutputType::Toml => {
+            check_toml_integers(config, "")?;
+            toml_edit::ser::to_string_pretty(&config).map_err(Error::from)
+        }
+    }
+}
+
+/// Checks every integer inside `value`, found at `path`, fits in TOML,
+/// which only supports 64-bit signed integers.
+fn check_toml_integers(value: &Value, path: &str) -> Result<(), Error> {
+    match value {
+        Value::Integer(int) if i64::try_from(*int).is_err() => Err(Error::Serializing(format!(
+            "integer `{int}` at `{path}` is out of range for TOML, which only supports 64-bit signed integers"
+        ))),
+        Value::Object(obj) => obj.iter().try_for_each(|(key, value)| {
+            let path = if path.is_empty() {
+                key.to_string()
+            } else {
+                format!("{path}.{key}")
+            };
+            check_toml_integers(value, &path)
+        }),
+        Value::Array(arr) => arr
+            .iter()
+            .enumerate()
+            .try_for_each(|(index, value)| check_toml_integers(value, &format!("{path}[{index}]"))),
+        _ => Ok(()),
     }
 }
 
-fn handle_err(error: &Error) {
+/// Prints an error from parsing `source`, read from `name`,
+/// and exits with the matching code.
+///
+/// Errors with a known location are shown as an annotated snippet of the source.
+fn handle_corn_err(error: CornError, name: &str, source: Option<&str>) -> ! {
+    exit(print_corn_err(error, name, source));
+}
+
+/// Prints an error from parsing `source`, read from `name`,
+/// in the same way as `handle_corn_err`, returning the matching exit code.
+fn print_corn_err(error: CornError, name: &str, source: Option<&str>) -> i32 {
+    match (error.diagnostic(), source) {
+        (Some(diagnostic), Some(source)) => {
+            let code = error.get_exit_code();
+            report::print_diagnostic(&diagnostic, Some(code), name, source);
+            code
+        }
+        _ => print_error(&Error::Corn(error)),
+    }
+}
+
+fn handle_err(error: &Error) -> ! {
+    exit(print_error(error));
+}
+
+/// Prints `error` with its code, returning the matching exit code.
+fn print_error(error: &Error) -> i32 {
     let code = error.get_exit_code();
     let code_formatted = format!("[E{code:0>4}]").red().bold();
 
     eprintln!("{code_formatted} {error}");
 
-    exit(code);
+    code
 }
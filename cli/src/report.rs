@@ -0,0 +1,140 @@
+use colored::{ColoredString, Colorize};
+use corn::{Diagnostic, Severity, Span};
+
+/// Prints `diagnostic` to `stderr` as an annotated snippet of `source`,
+/// with carets under each labelled region.
+pub fn print_diagnostic(diagnostic: &Diagnostic, code: Option<i32>, name: &str, source: &str) {
+    eprint!("{}", render(diagnostic, code, name, source));
+}
+
+/// A label to draw under a line of the snippet.
+struct Annotation<'a> {
+    line: usize,
+    start: usize,
+    end: usize,
+    message: Option<&'a str>,
+    primary: bool,
+}
+
+fn render(diagnostic: &Diagnostic, code: Option<i32>, name: &str, source: &str) -> String {
+    let lines = source.lines().collect::<Vec<_>>();
+
+    let mut annotations = std::iter::once(annotation(
+        diagnostic.span,
+        diagnostic.label.as_deref(),
+        true,
+        &lines,
+    ))
+    .chain(
+        diagnostic
+            .secondary
+            .iter()
+            .map(|label| annotation(label.span, Some(&label.message), false, &lines)),
+    )
+    .collect::<Vec<_>>();
+
+    annotations.sort_by_key(|annotation| annotation.line);
+
+    let gutter_width = annotations
+        .iter()
+        .map(|annotation| annotation.line.to_string().len())
+        .max()
+        .unwrap_or(1);
+
+    let gutter = |line: Option<usize>| {
+        let text = line.map_or_else(String::new, |line| line.to_string());
+        format!("{text:>gutter_width$} |").blue().bold()
+    };
+
+    let severity = match diagnostic.severity {
+        Severity::Error => "error".red().bold(),
+        Severity::Warning => "warning".yellow().bold(),
+    };
+
+    let code = code.map_or_else(String::new, |code| format!("[E{code:0>4}]"));
+
+    let mut out = format!(
+        "{severity}{}: {}\n",
+        code.as_str().red().bold(),
+        diagnostic.message.bold()
+    );
+
+    out.push_str(&format!(
+        "{:>gutter_width$}{} {name}:{}\n",
+        "",
+        "-->".blue().bold(),
+        diagnostic.span.start,
+    ));
+    out.push_str(&format!("{}\n", gutter(None)));
+
+    let mut previous_line = None;
+    for annotation in &annotations {
+        if previous_line != Some(annotation.line) {
+            let text = lines.get(annotation.line - 1).copied().unwrap_or_default();
+            out.push_str(&format!("{} {text}\n", gutter(Some(annotation.line))));
+            previous_line = Some(annotation.line);
+        }
+
+        let marker = if annotation.primary { "^" } else { "-" };
+        let underline = marker.repeat((annotation.end - annotation.start).max(1));
+        let message = annotation.message.unwrap_or_default();
+
+        let styled = |text: &str| -> ColoredString {
+            if annotation.primary {
+                text.red().bold()
+            } else {
+                text.blue().bold()
+            }
+        };
+
+        out.push_str(&format!(
+            "{} {:>indent$}{}\n",
+            gutter(None),
+            "",
+            styled(format!("{underline} {message}").trim_end()),
+            indent = annotation.start,
+        ));
+    }
+
+    if let Some(help) = &diagnostic.help {
+        out.push_str(&format!("{}\n", gutter(None)));
+        out.push_str(&format!(
+            "{:>gutter_width$} {} {help}\n",
+            "",
+            "= help:".bold()
+        ));
+    }
+
+    out.push('\n');
+    out
+}
+
+/// Gets the columns covered by `span` on the line it starts on.
+/// Spans over several lines are underlined up to the end of their first line.
+fn annotation<'a>(
+    span: Span,
+    message: Option<&'a str>,
+    primary: bool,
+    lines: &[&str],
+) -> Annotation<'a> {
+    let start = span.start;
+    let end = span.end;
+
+    let line_length = lines
+        .get(start.line - 1)
+        .map_or(0, |line| line.chars().count());
+
+    let end_column = if end.line == start.line {
+        end.column
+    } else {
+        line_length + 1
+    };
+
+    Annotation {
+        line: start.line,
+        start: start.column - 1,
+        end: end_column.max(start.column) - 1,
+        message,
+        primary,
+    }
+}
@@ -0,0 +1,490 @@
+//! Lossless concrete syntax tree for Corn documents.
+//!
+//! Unlike [`crate::parse`], which evaluates a document into a [`crate::Value`],
+//! the tree produced here keeps every byte of the input,
+//! including whitespace, comments and the original spelling of each literal.
+//! Writing a tree back out with [`Display`] reproduces the input exactly,
+//! which makes it suitable as a base for formatters and editing tools.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use corn::cst::{self, SyntaxKind};
+//!
+//! let input = "{\n    // the answer\n    foo = 0x2A\n}\n";
+//! let document = cst::parse(input).unwrap();
+//!
+//! assert_eq!(document.kind(), SyntaxKind::Document);
+//! assert_eq!(document.to_string(), input);
+//!
+//! let comment = document
+//!     .descendant_tokens()
+//!     .find(|token| token.kind() == SyntaxKind::Comment)
+//!     .unwrap();
+//!
+//! assert_eq!(comment.text(), "// the answer");
+//! ```
+
+use std::fmt::{Display, Formatter};
+use std::ops::Range;
+
+use pest::iterators::Pair;
+use pest::Parser;
+
+use crate::error::{Error, Result};
+use crate::parser::{describe_rules, AstParser};
+
+mod grammar {
+    #[derive(pest_derive::Parser)]
+    #[grammar = "cst.pest"]
+    pub struct CstParser;
+}
+
+use grammar::{CstParser, Rule};
+
+/// The kind of a node or token in the tree.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SyntaxKind {
+    // nodes
+    /// The root of the tree.
+    Document,
+    /// The `let { } in` block.
+    AssignBlock,
+    /// A `$input = value` entry inside the `let` block.
+    Assignment,
+    Object,
+    Array,
+    /// A `path = value` entry inside an object.
+    Pair,
+    /// A (possibly dotted) key.
+    Path,
+    /// A `..$input` entry inside an object or array.
+    Spread,
+    /// An `import "path"` expression inside an assignment.
+    Import,
+    /// A `$input as type` expression.
+    Cast,
+    /// A `$input ?? value` expression.
+    Fallback,
+    /// A `$input!"message"` expression.
+    Required,
+
+    // tokens
+    /// One or more consecutive whitespace characters.
+    Whitespace,
+    /// A `//` comment, excluding the trailing newline.
+    Comment,
+    LBrace,
+    RBrace,
+    LBracket,
+    RBracket,
+    Equals,
+    Dot,
+    SpreadOperator,
+    LetKeyword,
+    InKeyword,
+    ImportKeyword,
+    AsKeyword,
+    FallbackOperator,
+    RequiredOperator,
+    /// The type name in a cast expression.
+    CastType,
+    /// An unquoted segment of a path.
+    PathSegment,
+    /// A quoted segment of a path, including its quotes.
+    QuotedPathSegment,
+    Input,
+    /// A string literal, including its quotes.
+    String,
+    Integer,
+    Float,
+    Boolean,
+    Null,
+}
+
+impl SyntaxKind {
+    /// Whether this kind represents a leaf token,
+    /// rather than a node containing children.
+    #[must_use]
+    pub fn is_token(self) -> bool {
+        !matches!(
+            self,
+            SyntaxKind::Document
+                | SyntaxKind::AssignBlock
+                | SyntaxKind::Assignment
+                | SyntaxKind::Object
+                | SyntaxKind::Array
+                | SyntaxKind::Pair
+                | SyntaxKind::Path
+                | SyntaxKind::Spread
+                | SyntaxKind::Import
+                | SyntaxKind::Cast
+                | SyntaxKind::Fallback
+                | SyntaxKind::Required
+        )
+    }
+
+    /// Whether this kind has no meaning to the parser,
+    /// ie it is whitespace or a comment.
+    #[must_use]
+    pub fn is_trivia(self) -> bool {
+        matches!(self, SyntaxKind::Whitespace | SyntaxKind::Comment)
+    }
+
+    fn from_rule(rule: Rule) -> Self {
+        match rule {
+            Rule::document => SyntaxKind::Document,
+            Rule::assign_block => SyntaxKind::AssignBlock,
+            Rule::assignment => SyntaxKind::Assignment,
+            Rule::object => SyntaxKind::Object,
+            Rule::array => SyntaxKind::Array,
+            Rule::pair => SyntaxKind::Pair,
+            Rule::path => SyntaxKind::Path,
+            Rule::spread => SyntaxKind::Spread,
+            Rule::import => SyntaxKind::Import,
+            Rule::cast => SyntaxKind::Cast,
+            Rule::fallback => SyntaxKind::Fallback,
+            Rule::required => SyntaxKind::Required,
+            Rule::whitespace => SyntaxKind::Whitespace,
+            Rule::comment => SyntaxKind::Comment,
+            Rule::l_brace => SyntaxKind::LBrace,
+            Rule::r_brace => SyntaxKind::RBrace,
+            Rule::l_bracket => SyntaxKind::LBracket,
+            Rule::r_bracket => SyntaxKind::RBracket,
+            Rule::equals => SyntaxKind::Equals,
+            Rule::dot => SyntaxKind::Dot,
+            Rule::spread_operator => SyntaxKind::SpreadOperator,
+            Rule::let_keyword => SyntaxKind::LetKeyword,
+            Rule::in_keyword => SyntaxKind::InKeyword,
+            Rule::import_keyword => SyntaxKind::ImportKeyword,
+            Rule::as_keyword => SyntaxKind::AsKeyword,
+            Rule::fallback_operator => SyntaxKind::FallbackOperator,
+            Rule::required_operator => SyntaxKind::RequiredOperator,
+            Rule::cast_type => SyntaxKind::CastType,
+            Rule::regular_path_seg => SyntaxKind::PathSegment,
+            Rule::quoted_path_seg => SyntaxKind::QuotedPathSegment,
+            Rule::input => SyntaxKind::Input,
+            Rule::string => SyntaxKind::String,
+            Rule::integer => SyntaxKind::Integer,
+            Rule::float => SyntaxKind::Float,
+            Rule::boolean => SyntaxKind::Boolean,
+            Rule::null => SyntaxKind::Null,
+            _ => unreachable!(),
+        }
+    }
+}
+
+/// A node in the tree, containing child nodes and tokens.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SyntaxNode {
+    kind: SyntaxKind,
+    children: Vec<SyntaxElement>,
+}
+
+/// A leaf in the tree, holding a slice of the source text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SyntaxToken {
+    kind: SyntaxKind,
+    text: String,
+}
+
+/// Either a node or a token.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SyntaxElement {
+    Node(SyntaxNode),
+    Token(SyntaxToken),
+}
+
+impl SyntaxNode {
+    pub fn new(kind: SyntaxKind, children: Vec<SyntaxElement>) -> Self {
+        debug_assert!(!kind.is_token());
+        Self { kind, children }
+    }
+
+    #[must_use]
+    pub fn kind(&self) -> SyntaxKind {
+        self.kind
+    }
+
+    /// All direct children, in source order.
+    #[must_use]
+    pub fn children(&self) -> &[SyntaxElement] {
+        &self.children
+    }
+
+    /// Mutable access to the direct children.
+    ///
+    /// No validation is performed, so care must be taken
+    /// to keep the tree representing a valid document.
+    pub fn children_mut(&mut self) -> &mut Vec<SyntaxElement> {
+        &mut self.children
+    }
+
+    /// Iterates over direct children which are nodes.
+    pub fn child_nodes(&self) -> impl Iterator<Item = &SyntaxNode> {
+        self.children.iter().filter_map(SyntaxElement::as_node)
+    }
+
+    /// Iterates over direct children which are tokens.
+    pub fn child_tokens(&self) -> impl Iterator<Item = &SyntaxToken> {
+        self.children.iter().filter_map(SyntaxElement::as_token)
+    }
+
+    /// Gets the first direct child node of the given kind.
+    #[must_use]
+    pub fn child_node(&self, kind: SyntaxKind) -> Option<&SyntaxNode> {
+        self.child_nodes().find(|node| node.kind == kind)
+    }
+
+    /// Gets the first direct child token of the given kind.
+    #[must_use]
+    pub fn child_token(&self, kind: SyntaxKind) -> Option<&SyntaxToken> {
+        self.child_tokens().find(|token| token.kind == kind)
+    }
+
+    /// Iterates over every token below this node, in source order.
+    pub fn descendant_tokens(&self) -> impl Iterator<Item = &SyntaxToken> {
+        let mut stack = vec![self.children.iter()];
+
+        std::iter::from_fn(move || loop {
+            let iter = stack.last_mut()?;
+            match iter.next() {
+                Some(SyntaxElement::Token(token)) => return Some(token),
+                Some(SyntaxElement::Node(node)) => stack.push(node.children.iter()),
+                None => {
+                    stack.pop();
+                }
+            }
+        })
+    }
+
+    /// The length of the text covered by this node, in bytes.
+    #[must_use]
+    pub fn text_len(&self) -> usize {
+        self.children.iter().map(SyntaxElement::text_len).sum()
+    }
+}
+
+impl SyntaxToken {
+    pub fn new(kind: SyntaxKind, text: impl Into<String>) -> Self {
+        debug_assert!(kind.is_token());
+        Self {
+            kind,
+            text: text.into(),
+        }
+    }
+
+    #[must_use]
+    pub fn kind(&self) -> SyntaxKind {
+        self.kind
+    }
+
+    /// The source text of this token, exactly as written.
+    #[must_use]
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+}
+
+impl SyntaxElement {
+    #[must_use]
+    pub fn kind(&self) -> SyntaxKind {
+        match self {
+            SyntaxElement::Node(node) => node.kind,
+            SyntaxElement::Token(token) => token.kind,
+        }
+    }
+
+    #[must_use]
+    pub fn as_node(&self) -> Option<&SyntaxNode> {
+        match self {
+            SyntaxElement::Node(node) => Some(node),
+            SyntaxElement::Token(_) => None,
+        }
+    }
+
+    #[must_use]
+    pub fn as_token(&self) -> Option<&SyntaxToken> {
+        match self {
+            SyntaxElement::Node(_) => None,
+            SyntaxElement::Token(token) => Some(token),
+        }
+    }
+
+    /// The length of the text covered by this element, in bytes.
+    #[must_use]
+    pub fn text_len(&self) -> usize {
+        match self {
+            SyntaxElement::Node(node) => node.text_len(),
+            SyntaxElement::Token(token) => token.text.len(),
+        }
+    }
+}
+
+impl Display for SyntaxNode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.descendant_tokens()
+            .try_for_each(|token| f.write_str(&token.text))
+    }
+}
+
+impl Display for SyntaxToken {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.text)
+    }
+}
+
+impl Display for SyntaxElement {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SyntaxElement::Node(node) => node.fmt(f),
+            SyntaxElement::Token(token) => token.fmt(f),
+        }
+    }
+}
+
+impl From<SyntaxNode> for SyntaxElement {
+    fn from(node: SyntaxNode) -> Self {
+        SyntaxElement::Node(node)
+    }
+}
+
+impl From<SyntaxToken> for SyntaxElement {
+    fn from(token: SyntaxToken) -> Self {
+        SyntaxElement::Token(token)
+    }
+}
+
+fn build_element(pair: Pair<'_, Rule>) -> SyntaxElement {
+    let kind = SyntaxKind::from_rule(pair.as_rule());
+
+    if kind.is_token() {
+        return SyntaxElement::Token(SyntaxToken::new(kind, pair.as_str()));
+    }
+
+    let children = pair
+        .into_inner()
+        .filter(|pair| pair.as_rule() != Rule::EOI)
+        .map(build_element)
+        .collect();
+
+    SyntaxElement::Node(SyntaxNode::new(kind, children))
+}
+
+/// Parses the input string into a lossless syntax tree.
+///
+/// No evaluation takes place, so documents which contain
+/// undefined inputs or other semantic errors still produce a tree.
+///
+/// # Errors
+///
+/// Will fail if the input contains a syntax error.
+/// The returned error is the same as [`crate::parse`] would produce.
+pub fn parse(input: &str) -> Result<SyntaxNode> {
+    match CstParser::parse(Rule::document, input) {
+        Ok(mut pairs) => {
+            let document = pairs.next().expect("should contain a document rule");
+
+            match build_element(document) {
+                SyntaxElement::Node(node) => {
+                    debug_assert_eq!(node.text_len(), input.len());
+                    Ok(node)
+                }
+                SyntaxElement::Token(_) => unreachable!(),
+            }
+        }
+        // report errors using the main grammar, so they match `corn::parse`
+        Err(error) => match AstParser::parse(crate::Rule::config, input) {
+            Err(error) => Err(Error::ParserError(describe_rules(error))),
+            Ok(_) => Err(convert_error(&error, input)),
+        },
+    }
+}
+
+/// A part of a document which can be parsed on its own.
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Fragment {
+    Value,
+    Pair,
+    Assignment,
+    AssignBlock,
+}
+
+/// Parses a fragment of a document into a syntax element,
+/// without any surrounding trivia.
+pub(crate) fn parse_fragment(fragment: Fragment, input: &str) -> Result<SyntaxElement> {
+    let rule = match fragment {
+        Fragment::Value => Rule::standalone_value,
+        Fragment::Pair => Rule::standalone_pair,
+        Fragment::Assignment => Rule::standalone_assignment,
+        Fragment::AssignBlock => Rule::standalone_assign_block,
+    };
+
+    match CstParser::parse(rule, input) {
+        Ok(mut pairs) => Ok(build_element(
+            pairs.next().expect("should contain a fragment rule"),
+        )),
+        Err(error) => Err(convert_error(&error, input)),
+    }
+}
+
+/// Converts an error from the lossless grammar
+/// into an error of the main grammar's type.
+fn convert_error(error: &pest::error::Error<Rule>, input: &str) -> Error {
+    let offset = match error.location {
+        pest::error::InputLocation::Pos(pos) => pos,
+        pest::error::InputLocation::Span((start, _)) => start,
+    };
+
+    Error::ParserError(Box::new(pest::error::Error::new_from_pos(
+        pest::error::ErrorVariant::CustomError {
+            message: error.variant.message().to_string(),
+        },
+        pest::Position::new(input, offset).unwrap_or_else(|| pest::Position::from_start(input)),
+    )))
+}
+
+/// A change which removes a syntax error from a document,
+/// without moving the text around it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Repair {
+    /// The text in the byte range cannot be parsed and should be blanked out.
+    Blank(Range<usize>),
+    /// A closing bracket is missing at the offset.
+    Insert(usize, &'static str),
+}
+
+impl Repair {
+    pub(crate) fn start(&self) -> usize {
+        match self {
+            Repair::Blank(range) => range.start,
+            Repair::Insert(offset, _) => *offset,
+        }
+    }
+}
+
+/// Finds the repairs needed to make each syntax error
+/// in the input parseable, in source order.
+///
+/// Returns `None` if the errors are too severe to recover from,
+/// such as the document not containing an object at all.
+pub(crate) fn find_repairs(input: &str) -> Option<Vec<Repair>> {
+    let pairs = CstParser::parse(Rule::recovering_document, input).ok()?;
+
+    let repairs = pairs
+        .flatten()
+        .filter_map(|pair| {
+            let span = pair.as_span();
+            match pair.as_rule() {
+                Rule::invalid | Rule::invalid_trailing => {
+                    Some(Repair::Blank(span.start()..span.end()))
+                }
+                Rule::missing_brace => Some(Repair::Insert(span.start(), "}")),
+                Rule::missing_bracket => Some(Repair::Insert(span.start(), "]")),
+                _ => None,
+            }
+        })
+        .collect();
+
+    Some(repairs)
+}
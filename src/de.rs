@@ -1,25 +1,348 @@
+use std::borrow::Cow;
+use std::cell::RefCell;
 use std::collections::VecDeque;
+use std::fmt::{Display, Formatter};
+use std::rc::Rc;
 
 use serde::de::{self, DeserializeSeed, EnumAccess, IntoDeserializer, VariantAccess, Visitor};
 
-use crate::error::{Error, Result};
-use crate::parse;
-use crate::Value;
+use crate::error::{Context, Error, Result};
+use crate::options::ConversionOptions;
+use crate::ser::format_key;
+use crate::{DeserializerOptions, Span, Spanned, SpannedValue, UnknownKeys, Value};
 
+mod value;
+
+/// A serde deserializer for a parsed Corn document.
+///
+/// Most programs can use [`from_str`] or [`DeserializerOptions::from_str`] instead.
+/// Using the deserializer directly allows it to be passed to other serde tools,
+/// and for the keys it skipped to be read afterwards.
+///
+/// # Examples
+///
+/// ```rust
+/// use corn::{Deserializer, DeserializerOptions};
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct Config {
+///     port: u16,
+/// }
+///
+/// let options = DeserializerOptions::new();
+/// let mut deserializer = Deserializer::from_str_with_options("{ port = 8080 prot = 80 }", &options).unwrap();
+///
+/// let config = Config::deserialize(&mut deserializer).unwrap();
+/// assert_eq!(config.port, 8080);
+/// assert_eq!(deserializer.unknown_keys()[0].path, "prot");
+/// ```
 #[derive(Debug)]
 pub struct Deserializer<'de> {
     value: Option<Value<'de>>,
+    scope: Scope<'de>,
 }
 
 impl<'de> Deserializer<'de> {
+    /// Parses the input string, creating a deserializer for the document.
+    ///
+    /// # Errors
+    ///
+    /// Fails under the same conditions as [`crate::parse`].
+    // `FromStr` cannot be implemented, as the deserializer borrows from the input
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(input: &'de str) -> Result<Self> {
-        let parsed = parse(input)?;
+        Self::from_str_with_options(input, &DeserializerOptions::default())
+    }
+
+    /// Parses the input string using `options`,
+    /// creating a deserializer for the document.
+    ///
+    /// # Errors
+    ///
+    /// Fails under the same conditions as [`crate::parse`].
+    pub fn from_str_with_options(
+        input: &'de str,
+        options: &DeserializerOptions<'de>,
+    ) -> Result<Self> {
+        let parsed = options.parse.parse_spanned(input)?;
+        let value = Value::from(parsed.clone());
+
+        Ok(Self::new(
+            value,
+            Scope {
+                path: vec![],
+                options: options.conversion,
+                source: Some(Rc::new(parsed)),
+                unknown_keys: Rc::default(),
+            },
+        ))
+    }
+
+    /// Creates a deserializer for an existing value.
+    ///
+    /// As the value has no source document,
+    /// errors include the path to the value which failed but not its span.
+    #[must_use]
+    pub fn from_value(value: Value<'de>) -> Self {
+        Self::from_value_with_options(value, &DeserializerOptions::default())
+    }
+
+    /// Creates a deserializer for an existing value using `options`.
+    /// The parse options are not used.
+    #[must_use]
+    pub fn from_value_with_options(value: Value<'de>, options: &DeserializerOptions) -> Self {
+        Self::new(
+            value,
+            Scope {
+                path: vec![],
+                options: options.conversion,
+                source: None,
+                unknown_keys: Rc::default(),
+            },
+        )
+    }
+
+    fn new(value: Value<'de>, scope: Scope<'de>) -> Self {
+        Self {
+            value: Some(value),
+            scope,
+        }
+    }
+
+    /// Deserializes the value into `T`,
+    /// attaching the location of the root value to any error without one.
+    pub(crate) fn deserialize<T>(&mut self) -> Result<T>
+    where
+        T: de::Deserialize<'de>,
+    {
+        let scope = self.scope.clone();
+        T::deserialize(self).map_err(|err| scope.locate(err, false))
+    }
+
+    /// Gets every key which has been skipped so far
+    /// because the type being deserialized into does not use it.
+    ///
+    /// Always empty when unknown keys are denied.
+    #[must_use]
+    pub fn unknown_keys(&self) -> Vec<UnknownKey> {
+        self.scope.unknown_keys.borrow().clone()
+    }
+
+    /// Converts `value` into the integer type `name`,
+    /// failing if it is outside of that type's range.
+    fn integer<T>(value: i128, name: &str) -> Result<T>
+    where
+        T: TryFrom<i128>,
+    {
+        T::try_from(value).map_err(|_| out_of_range(format!("integer `{value}`"), name))
+    }
+
+    /// Converts `value` into an integer if it has no fractional part.
+    /// Only used when [`DeserializerOptions::float_to_int`] is enabled.
+    fn float_to_integer(value: f64) -> Result<i128> {
+        // `i128::MAX as f64` rounds up to 2^127, which is itself out of range
+        let in_range = value >= i128::MIN as f64 && value < i128::MAX as f64;
+
+        if value.fract() == 0.0 && in_range {
+            Ok(value as i128)
+        } else {
+            Err(error(format!(
+                "float `{value:?}` cannot be converted to an integer without losing precision"
+            )))
+        }
+    }
+
+    /// Converts `value` into an `f32`,
+    /// failing if it is too large to be represented.
+    fn float(value: f64) -> Result<f32> {
+        let float = value as f32;
+
+        if float.is_infinite() && value.is_finite() {
+            Err(out_of_range(format!("float `{value:?}`"), "f32"))
+        } else {
+            Ok(float)
+        }
+    }
+}
+
+/// Creates an error which is not yet attached to a location.
+/// The location is added as the error is returned from the value it occurred in.
+fn error(message: impl Into<String>) -> Error {
+    Error::DeserializationError {
+        message: message.into(),
+        path: None,
+        context: None,
+    }
+}
+
+fn out_of_range(value: String, name: &str) -> Error {
+    error(format!("{value} is out of range for {name}"))
+}
+
+/// A key in the document which is not used by the type it was deserialized into,
+/// such as a misspelled field name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownKey {
+    /// The location of the key, such as `server.sever`.
+    pub path: String,
+    /// The region of the document containing the key.
+    pub span: Option<Span>,
+}
 
-        Ok(Self::from_value(parsed))
+impl Display for UnknownKey {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "unknown key `{}`", self.path)
     }
+}
+
+/// A single step in the path to a value.
+#[derive(Debug, Clone)]
+enum Segment {
+    Key(String),
+    Index(usize),
+}
 
-    fn from_value(value: Value<'de>) -> Self {
-        Self { value: Some(value) }
+/// Describes the type of `value`, such as "integer", for use in error messages.
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Object(_) => "object",
+        Value::Array(_) => "array",
+        Value::String(_) => "string",
+        Value::Integer(_) => "integer",
+        Value::Float(_) => "float",
+        Value::Boolean(_) => "boolean",
+        Value::Null(_) => "null",
+    }
+}
+
+/// Where the value being deserialized came from,
+/// shared with the deserializers of each of its children.
+#[derive(Debug, Clone)]
+struct Scope<'de> {
+    /// Location of the value within the document. Empty for the root value.
+    path: Vec<Segment>,
+    options: ConversionOptions,
+    /// The tree the value was converted from, used to find the span of errors.
+    /// Not available when deserializing a value created without parsing.
+    source: Option<Rc<Spanned<SpannedValue<'de>>>>,
+    /// Keys skipped anywhere in the document.
+    unknown_keys: Rc<RefCell<Vec<UnknownKey>>>,
+}
+
+impl Scope<'_> {
+    /// Creates the scope for a child of this scope's value.
+    fn child(&self, segment: Segment) -> Self {
+        let mut path = self.path.clone();
+        path.push(segment);
+
+        Self {
+            path,
+            options: self.options,
+            source: self.source.clone(),
+            unknown_keys: self.unknown_keys.clone(),
+        }
+    }
+
+    /// Handles the value of a key which the type being deserialized into does not use.
+    ///
+    /// The key is either recorded, or rejected if unknown keys are denied.
+    fn unknown_key(&self) -> Result<()> {
+        if !matches!(self.path.last(), Some(Segment::Key(_))) {
+            return Ok(());
+        }
+
+        match self.options.unknown_keys {
+            UnknownKeys::Ignore => {
+                self.unknown_keys.borrow_mut().push(UnknownKey {
+                    path: Path(&self.path).to_string(),
+                    span: self.span(true),
+                });
+
+                Ok(())
+            }
+            UnknownKeys::Deny => Err(self.locate(error("unknown key"), true)),
+        }
+    }
+
+    /// Attaches the path and span of this scope's value to `err`,
+    /// unless it has already been attached to a more specific location.
+    ///
+    /// If `key` is set, the span points at the key the value was assigned to
+    /// rather than the value itself.
+    fn locate(&self, err: Error, key: bool) -> Error {
+        match err {
+            Error::DeserializationError {
+                message,
+                path: None,
+                context: None,
+            } => Error::DeserializationError {
+                message,
+                path: (!self.path.is_empty()).then(|| Path(&self.path).to_string()),
+                context: self.span(key).map(|span| Box::new(Context::new(span))),
+            },
+            err => err,
+        }
+    }
+
+    /// Finds the span of this scope's value in the source tree.
+    /// If the path cannot be followed to the end, gives the span of the last value found.
+    fn span(&self, key: bool) -> Option<Span> {
+        let mut node = self.source.as_deref()?;
+        let mut span = node.span;
+
+        for (index, segment) in self.path.iter().enumerate() {
+            let is_last = index == self.path.len() - 1;
+
+            match (segment, &node.value) {
+                (Segment::Key(name), SpannedValue::Object(obj)) => {
+                    let Some(entry) = obj.get(name.as_str()) else {
+                        break;
+                    };
+
+                    span = if key && is_last {
+                        entry.key
+                    } else {
+                        entry.value.span
+                    };
+                    node = &entry.value;
+                }
+                (Segment::Index(index), SpannedValue::Array(arr)) => {
+                    let Some(element) = arr.get(*index) else {
+                        break;
+                    };
+
+                    span = element.span;
+                    node = element;
+                }
+                _ => break,
+            }
+        }
+
+        Some(span)
+    }
+}
+
+/// Displays a path in the form `server.listeners[2].port`.
+struct Path<'a>(&'a [Segment]);
+
+impl Display for Path<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for (index, segment) in self.0.iter().enumerate() {
+            match segment {
+                Segment::Key(key) => {
+                    if index > 0 {
+                        write!(f, ".")?;
+                    }
+
+                    let key = format_key(key).unwrap_or(Cow::Borrowed(key));
+                    write!(f, "{key}")?;
+                }
+                Segment::Index(index) => write!(f, "[{index}]")?,
+            }
+        }
+
+        Ok(())
     }
 }
 
@@ -32,8 +355,46 @@ pub fn from_str<T>(s: &str) -> Result<T>
 where
     T: de::DeserializeOwned,
 {
-    let mut deserializer = Deserializer::from_str(s)?;
-    T::deserialize(&mut deserializer)
+    DeserializerOptions::default().from_str(s)
+}
+
+/// Deserializes an existing value into `T`,
+/// such as a config which has been parsed and then merged with another.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::collections::HashMap;
+///
+/// let value = corn::parse("{ port = 8080 }").unwrap();
+/// let config: HashMap<String, u16> = corn::from_value(value).unwrap();
+///
+/// assert_eq!(config["port"], 8080);
+/// ```
+///
+/// # Errors
+///
+/// Will return a `DeserializationError` if the value does not match `T`.
+pub fn from_value<T>(value: Value) -> Result<T>
+where
+    T: de::DeserializeOwned,
+{
+    Deserializer::from_value(value).deserialize()
+}
+
+/// Deserializes a borrowed value into `T`,
+/// leaving the value in place.
+///
+/// The value is cloned before it is deserialized.
+///
+/// # Errors
+///
+/// Will return a `DeserializationError` if the value does not match `T`.
+pub fn from_value_ref<T>(value: &Value) -> Result<T>
+where
+    T: de::DeserializeOwned,
+{
+    from_value(value.clone())
 }
 
 /// Attempts to deserialize the config from a byte slice.
@@ -45,37 +406,29 @@ pub fn from_slice<T>(bytes: &[u8]) -> Result<T>
 where
     T: de::DeserializeOwned,
 {
-    match std::str::from_utf8(bytes) {
-        Ok(s) => from_str(s),
-        Err(e) => Err(Error::DeserializationError(e.to_string())),
-    }
+    DeserializerOptions::default().from_slice(bytes)
 }
 
 macro_rules! get_value {
     ($self:ident) => {
         match $self.value.take() {
             Some(val) => Ok(val),
-            None => Err(Error::DeserializationError(String::from(
-                "Deserializer value unexpectedly `None`",
-            ))),
+            None => Err(error("Deserializer value unexpectedly `None`")),
         }?
     };
 }
 
 macro_rules! err_expected {
     ($expected:literal, $got:expr) => {
-        Err(Error::DeserializationError(format!(
-            "Expected {}, found '{:?}'",
-            $expected, $got
-        )))
+        Err(error(format!("Expected {}, found '{:?}'", $expected, $got)))
     };
 }
 
 macro_rules! match_value {
-    ($self:ident, $name:literal, $($pat:pat => $expr:expr)+) => {{
+    ($self:ident, $name:literal, $($pat:pat $(if $guard:expr)? => $expr:expr)+) => {{
         let value = get_value!($self);
         match value {
-            $($pat => $expr, )+
+            $($pat $(if $guard)? => $expr, )+
             _ => err_expected!($name, value)
         }
     }};
@@ -91,15 +444,23 @@ impl<'de> de::Deserializer<'de> for &mut Deserializer<'de> {
         let value = get_value!(self);
         match value {
             Value::Object(_) => {
-                let map = Map::new(value);
+                let map = Map::new(value, self.scope.clone());
                 visitor.visit_map(map)
             }
             Value::Array(_) => {
-                let seq = Seq::new(value);
+                let seq = Seq::new(value, self.scope.clone());
                 visitor.visit_seq(seq)
             }
             Value::String(val) => visitor.visit_str(&val),
-            Value::Integer(val) => visitor.visit_i64(val),
+            Value::Integer(val) => {
+                if let Ok(val) = i64::try_from(val) {
+                    visitor.visit_i64(val)
+                } else if let Ok(val) = u64::try_from(val) {
+                    visitor.visit_u64(val)
+                } else {
+                    visitor.visit_i128(val)
+                }
+            }
             Value::Float(val) => visitor.visit_f64(val),
             Value::Boolean(val) => visitor.visit_bool(val),
             Value::Null(_) => visitor.visit_unit(),
@@ -117,70 +478,111 @@ impl<'de> de::Deserializer<'de> for &mut Deserializer<'de> {
     where
         V: Visitor<'de>,
     {
-        match_value!(self, "integer (i8)", Value::Integer(val) =>  visitor.visit_i8(val as i8))
+        match_value!(self, "integer (i8)", Value::Integer(val) =>  visitor.visit_i8(Deserializer::integer(val, "i8")?)
+            Value::Float(val) if self.scope.options.float_to_int => visitor.visit_i8(Deserializer::integer(Deserializer::float_to_integer(val)?, "i8")?)
+        )
     }
 
     fn deserialize_i16<V>(self, visitor: V) -> std::result::Result<V::Value, Self::Error>
     where
         V: Visitor<'de>,
     {
-        match_value!(self, "integer (i16)", Value::Integer(val) =>  visitor.visit_i16(val as i16))
+        match_value!(self, "integer (i16)", Value::Integer(val) =>  visitor.visit_i16(Deserializer::integer(val, "i16")?)
+            Value::Float(val) if self.scope.options.float_to_int => visitor.visit_i16(Deserializer::integer(Deserializer::float_to_integer(val)?, "i16")?)
+        )
     }
 
     fn deserialize_i32<V>(self, visitor: V) -> std::result::Result<V::Value, Self::Error>
     where
         V: Visitor<'de>,
     {
-        match_value!(self, "integer (i32)", Value::Integer(val) =>  visitor.visit_i32(val as i32))
+        match_value!(self, "integer (i32)", Value::Integer(val) =>  visitor.visit_i32(Deserializer::integer(val, "i32")?)
+            Value::Float(val) if self.scope.options.float_to_int => visitor.visit_i32(Deserializer::integer(Deserializer::float_to_integer(val)?, "i32")?)
+        )
     }
 
     fn deserialize_i64<V>(self, visitor: V) -> std::result::Result<V::Value, Self::Error>
     where
         V: Visitor<'de>,
     {
-        match_value!(self, "integer (i64)", Value::Integer(val) =>  visitor.visit_i64(val))
+        match_value!(self, "integer (i64)", Value::Integer(val) =>  visitor.visit_i64(Deserializer::integer(val, "i64")?)
+            Value::Float(val) if self.scope.options.float_to_int => visitor.visit_i64(Deserializer::integer(Deserializer::float_to_integer(val)?, "i64")?)
+        )
+    }
+
+    fn deserialize_i128<V>(self, visitor: V) -> std::result::Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        match_value!(self, "integer (i128)",
+            Value::Integer(val) =>  visitor.visit_i128(val)
+            Value::Float(val) if self.scope.options.float_to_int => visitor.visit_i128(Deserializer::float_to_integer(val)?)
+        )
     }
 
     fn deserialize_u8<V>(self, visitor: V) -> std::result::Result<V::Value, Self::Error>
     where
         V: Visitor<'de>,
     {
-        match_value!(self, "integer (u8)", Value::Integer(val) =>  visitor.visit_u8(val as u8))
+        match_value!(self, "integer (u8)", Value::Integer(val) =>  visitor.visit_u8(Deserializer::integer(val, "u8")?)
+            Value::Float(val) if self.scope.options.float_to_int => visitor.visit_u8(Deserializer::integer(Deserializer::float_to_integer(val)?, "u8")?)
+        )
     }
 
     fn deserialize_u16<V>(self, visitor: V) -> std::result::Result<V::Value, Self::Error>
     where
         V: Visitor<'de>,
     {
-        match_value!(self, "integer (u16)", Value::Integer(val) =>  visitor.visit_u16(val as u16))
+        match_value!(self, "integer (u16)", Value::Integer(val) =>  visitor.visit_u16(Deserializer::integer(val, "u16")?)
+            Value::Float(val) if self.scope.options.float_to_int => visitor.visit_u16(Deserializer::integer(Deserializer::float_to_integer(val)?, "u16")?)
+        )
     }
 
     fn deserialize_u32<V>(self, visitor: V) -> std::result::Result<V::Value, Self::Error>
     where
         V: Visitor<'de>,
     {
-        match_value!(self, "integer (u32)", Value::Integer(val) =>  visitor.visit_u32(val as u32))
+        match_value!(self, "integer (u32)", Value::Integer(val) =>  visitor.visit_u32(Deserializer::integer(val, "u32")?)
+            Value::Float(val) if self.scope.options.float_to_int => visitor.visit_u32(Deserializer::integer(Deserializer::float_to_integer(val)?, "u32")?)
+        )
     }
 
     fn deserialize_u64<V>(self, visitor: V) -> std::result::Result<V::Value, Self::Error>
     where
         V: Visitor<'de>,
     {
-        match_value!(self, "integer (u64)", Value::Integer(val) =>  visitor.visit_u64(val as u64))
+        match_value!(self, "integer (u64)", Value::Integer(val) =>  visitor.visit_u64(Deserializer::integer(val, "u64")?)
+            Value::Float(val) if self.scope.options.float_to_int => visitor.visit_u64(Deserializer::integer(Deserializer::float_to_integer(val)?, "u64")?)
+        )
+    }
+
+    fn deserialize_u128<V>(self, visitor: V) -> std::result::Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        match_value!(self, "integer (u128)", Value::Integer(val) =>  visitor.visit_u128(Deserializer::integer(val, "u128")?)
+            Value::Float(val) if self.scope.options.float_to_int => visitor.visit_u128(Deserializer::integer(Deserializer::float_to_integer(val)?, "u128")?)
+        )
     }
 
     fn deserialize_f32<V>(self, visitor: V) -> std::result::Result<V::Value, Self::Error>
     where
         V: Visitor<'de>,
     {
-        match_value!(self, "float (f32)", Value::Float(val) =>  visitor.visit_f32(val as f32))
+        match_value!(self, "float (f32)",
+            Value::Float(val) =>  visitor.visit_f32(Deserializer::float(val)?)
+            Value::Integer(val) if self.scope.options.int_to_float => visitor.visit_f32(val as f32)
+        )
     }
 
     fn deserialize_f64<V>(self, visitor: V) -> std::result::Result<V::Value, Self::Error>
     where
         V: Visitor<'de>,
     {
-        match_value!(self, "float (f64)", Value::Float(val) =>  visitor.visit_f64(val))
+        match_value!(self, "float (f64)",
+            Value::Float(val) =>  visitor.visit_f64(val)
+            Value::Integer(val) if self.scope.options.int_to_float => visitor.visit_f64(val as f64)
+        )
     }
 
     fn deserialize_char<V>(self, visitor: V) -> std::result::Result<V::Value, Self::Error>
@@ -238,7 +640,7 @@ impl<'de> de::Deserializer<'de> for &mut Deserializer<'de> {
         let value = get_value!(self);
         match value {
             Value::Null(_) => visitor.visit_none(),
-            _ => visitor.visit_some(&mut Deserializer::from_value(value)),
+            _ => visitor.visit_some(&mut Deserializer::new(value, self.scope.clone())),
         }
     }
 
@@ -277,7 +679,7 @@ impl<'de> de::Deserializer<'de> for &mut Deserializer<'de> {
     {
         let value = get_value!(self);
         match value {
-            Value::Array(_) => visitor.visit_seq(Seq::new(value)),
+            Value::Array(_) => visitor.visit_seq(Seq::new(value, self.scope.clone())),
             _ => err_expected!("array", value),
         }
     }
@@ -311,7 +713,7 @@ impl<'de> de::Deserializer<'de> for &mut Deserializer<'de> {
     {
         let value = get_value!(self);
         match value {
-            Value::Object(_) => visitor.visit_map(Map::new(value)),
+            Value::Object(_) => visitor.visit_map(Map::new(value, self.scope.clone())),
             _ => err_expected!("object", value),
         }
     }
@@ -339,7 +741,7 @@ impl<'de> de::Deserializer<'de> for &mut Deserializer<'de> {
     {
         let value = get_value!(self);
         match value {
-            Value::Object(_) => visitor.visit_enum(Enum::new(value)),
+            Value::Object(_) => visitor.visit_enum(Enum::new(value, self.scope.clone())),
             Value::String(val) => visitor.visit_enum(val.into_deserializer()),
             _ => err_expected!("object or string (enum variant)", value),
         }
@@ -356,22 +758,28 @@ impl<'de> de::Deserializer<'de> for &mut Deserializer<'de> {
     where
         V: Visitor<'de>,
     {
-        self.deserialize_any(visitor)
+        // the value is skipped without being visited,
+        // so that keys inside an unknown key are not also reported
+        get_value!(self);
+        self.scope.unknown_key()?;
+        visitor.visit_unit()
     }
 }
 
 struct Map<'de> {
-    values: VecDeque<Value<'de>>,
+    entries: VecDeque<(Cow<'de, str>, Value<'de>)>,
+    /// The value of the key most recently returned by `next_key_seed`.
+    value: Option<(Scope<'de>, Value<'de>)>,
+    scope: Scope<'de>,
 }
 
 impl<'de> Map<'de> {
-    fn new(value: Value<'de>) -> Self {
+    fn new(value: Value<'de>, scope: Scope<'de>) -> Self {
         match value {
             Value::Object(values) => Self {
-                values: values
-                    .into_iter()
-                    .flat_map(|(key, value)| vec![Value::String(key), value])
-                    .collect(),
+                entries: values.into_iter().collect(),
+                value: None,
+                scope,
             },
             _ => unreachable!(),
         }
@@ -385,9 +793,15 @@ impl<'de> de::MapAccess<'de> for Map<'de> {
     where
         K: DeserializeSeed<'de>,
     {
-        if let Some(value) = self.values.pop_front() {
-            seed.deserialize(&mut Deserializer::from_value(value))
-                .map(Some)
+        if let Some((key, value)) = self.entries.pop_front() {
+            let scope = self.scope.child(Segment::Key(key.to_string()));
+
+            let key = seed
+                .deserialize(&mut Deserializer::new(Value::String(key), scope.clone()))
+                .map_err(|err| scope.locate(err, true))?;
+
+            self.value = Some((scope, value));
+            Ok(Some(key))
         } else {
             Ok(None)
         }
@@ -397,28 +811,32 @@ impl<'de> de::MapAccess<'de> for Map<'de> {
     where
         V: DeserializeSeed<'de>,
     {
-        match self.values.pop_front() {
-            Some(value) => seed.deserialize(&mut Deserializer::from_value(value)),
-            None => Err(Error::DeserializationError(
-                "Expected value to exist".to_string(),
-            )),
+        match self.value.take() {
+            Some((scope, value)) => seed
+                .deserialize(&mut Deserializer::new(value, scope.clone()))
+                .map_err(|err| scope.locate(err, false)),
+            None => Err(error("Expected value to exist")),
         }
     }
 
     fn size_hint(&self) -> Option<usize> {
-        Some(self.values.len() / 2)
+        Some(self.entries.len())
     }
 }
 
 struct Seq<'de> {
     values: VecDeque<Value<'de>>,
+    index: usize,
+    scope: Scope<'de>,
 }
 
 impl<'de> Seq<'de> {
-    fn new(value: Value<'de>) -> Self {
+    fn new(value: Value<'de>, scope: Scope<'de>) -> Self {
         match value {
             Value::Array(values) => Self {
                 values: VecDeque::from(values),
+                index: 0,
+                scope,
             },
             _ => unreachable!(),
         }
@@ -436,8 +854,12 @@ impl<'de> de::SeqAccess<'de> for Seq<'de> {
         T: DeserializeSeed<'de>,
     {
         if let Some(value) = self.values.pop_front() {
-            seed.deserialize(&mut Deserializer::from_value(value))
+            let scope = self.scope.child(Segment::Index(self.index));
+            self.index += 1;
+
+            seed.deserialize(&mut Deserializer::new(value, scope.clone()))
                 .map(Some)
+                .map_err(|err| scope.locate(err, false))
         } else {
             Ok(None)
         }
@@ -450,11 +872,12 @@ impl<'de> de::SeqAccess<'de> for Seq<'de> {
 
 struct Enum<'de> {
     value: Value<'de>,
+    scope: Scope<'de>,
 }
 
 impl<'de> Enum<'de> {
-    fn new(value: Value<'de>) -> Self {
-        Self { value }
+    fn new(value: Value<'de>, scope: Scope<'de>) -> Self {
+        Self { value, scope }
     }
 }
 
@@ -468,19 +891,31 @@ impl<'de> EnumAccess<'de> for Enum<'de> {
     {
         match self.value {
             Value::String(_) => {
-                let value = seed.deserialize(&mut Deserializer::from_value(self.value))?;
-                Ok((value, Variant::new(None)))
+                let value = seed
+                    .deserialize(&mut Deserializer::new(self.value, self.scope.clone()))
+                    .map_err(|err| self.scope.locate(err, false))?;
+                Ok((value, Variant::new(None, self.scope)))
+            }
+            Value::Object(obj) if self.scope.options.strict_enums && obj.len() > 1 => {
+                Err(self.scope.locate(
+                    error(format!(
+                        "expected an object with a single key for an enum variant, found {} keys",
+                        obj.len()
+                    )),
+                    false,
+                ))
             }
             Value::Object(obj) => {
                 let first_pair = obj.into_iter().next();
                 if let Some(first_pair) = first_pair {
+                    let scope = self.scope.child(Segment::Key(first_pair.0.to_string()));
                     let value = Value::String(first_pair.0);
-                    let tag = seed.deserialize(&mut Deserializer::from_value(value))?;
-                    Ok((tag, Variant::new(Some(first_pair.1))))
+                    let tag = seed
+                        .deserialize(&mut Deserializer::new(value, scope.clone()))
+                        .map_err(|err| scope.locate(err, true))?;
+                    Ok((tag, Variant::new(Some(first_pair.1), scope)))
                 } else {
-                    Err(Error::DeserializationError(
-                        "Cannot deserialize empty object into enum".to_string(),
-                    ))
+                    Err(error("Cannot deserialize empty object into enum"))
                 }
             }
             _ => unreachable!(),
@@ -490,11 +925,30 @@ impl<'de> EnumAccess<'de> for Enum<'de> {
 
 struct Variant<'de> {
     value: Option<Value<'de>>,
+    scope: Scope<'de>,
 }
 
 impl<'de> Variant<'de> {
-    fn new(value: Option<Value<'de>>) -> Self {
-        Self { value }
+    fn new(value: Option<Value<'de>>, scope: Scope<'de>) -> Self {
+        Self { value, scope }
+    }
+
+    /// Creates a located error for a variant whose value
+    /// does not have the shape required by its `kind`, such as a struct variant.
+    fn wrong_shape(&self, expected: &str, kind: &str, found: Option<&Value>) -> Error {
+        let name = match self.scope.path.last() {
+            Some(Segment::Key(name)) => format!(" `{name}`"),
+            _ => String::new(),
+        };
+
+        let found = found.map_or("no value", type_name);
+
+        self.scope.locate(
+            error(format!(
+                "expected {expected} for {kind} variant{name}, found {found}"
+            )),
+            false,
+        )
     }
 }
 
@@ -510,34 +964,42 @@ impl<'de> VariantAccess<'de> for Variant<'de> {
         T: DeserializeSeed<'de>,
     {
         match self.value {
-            Some(value) => seed.deserialize(&mut Deserializer::from_value(value)),
-            None => Err(Error::DeserializationError(
-                "Expected value to exist".to_string(),
-            )),
+            Some(value) => seed
+                .deserialize(&mut Deserializer::new(value, self.scope.clone()))
+                .map_err(|err| self.scope.locate(err, false)),
+            None => Err(error("Expected value to exist")),
         }
     }
 
-    fn tuple_variant<V>(self, _len: usize, visitor: V) -> std::result::Result<V::Value, Self::Error>
+    fn tuple_variant<V>(
+        mut self,
+        _len: usize,
+        visitor: V,
+    ) -> std::result::Result<V::Value, Self::Error>
     where
         V: Visitor<'de>,
     {
-        match self.value {
-            Some(value) if matches!(value, Value::Array(_)) => visitor.visit_seq(Seq::new(value)),
-            _ => unreachable!(),
+        match self.value.take() {
+            Some(value @ Value::Array(_)) => visitor
+                .visit_seq(Seq::new(value, self.scope.clone()))
+                .map_err(|err| self.scope.locate(err, false)),
+            value => Err(self.wrong_shape("array", "tuple", value.as_ref())),
         }
     }
 
     fn struct_variant<V>(
-        self,
+        mut self,
         _fields: &'static [&'static str],
         visitor: V,
     ) -> std::result::Result<V::Value, Self::Error>
     where
         V: Visitor<'de>,
     {
-        match self.value {
-            Some(value) if matches!(value, Value::Object(_)) => visitor.visit_map(Map::new(value)),
-            _ => unreachable!(),
+        match self.value.take() {
+            Some(value @ Value::Object(_)) => visitor
+                .visit_map(Map::new(value, self.scope.clone()))
+                .map_err(|err| self.scope.locate(err, false)),
+            value => Err(self.wrong_shape("object", "struct", value.as_ref())),
         }
     }
 }
@@ -0,0 +1,263 @@
+//! Diagnostics describing every problem found in a document.
+//!
+//! Unlike [`crate::parse`], which stops at the first error,
+//! [`parse_all_diagnostics`] recovers from syntax errors
+//! by skipping the entry containing them, and keeps evaluating
+//! the rest of the document to find any further errors.
+//!
+//! [`Error::diagnostic`](crate::error::Error::diagnostic) gives the same details for a single error returned by [`crate::parse`].
+
+use std::fmt::{Display, Formatter};
+use std::path::PathBuf;
+
+use pest::error::{InputLocation, LineColLocation};
+
+use crate::cst::{find_repairs, Repair};
+use crate::error::Context;
+use crate::options::ParseOptions;
+use crate::parser::diagnose_in_chain;
+use crate::span::{LineIndex, Position, Span};
+use crate::Rule;
+
+/// How serious a diagnostic is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Severity {
+    /// The document is invalid and cannot be parsed.
+    Error,
+    /// The document can be parsed, but likely contains a mistake.
+    Warning,
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Severity::Error => write!(f, "error"),
+            Severity::Warning => write!(f, "warning"),
+        }
+    }
+}
+
+/// A message attached to a region of a document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Label {
+    pub span: Span,
+    pub message: String,
+}
+
+/// A single problem found in a document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub message: String,
+    /// The region of the document the problem was found in.
+    pub span: Span,
+    /// Describes the problem at `span`.
+    pub label: Option<String>,
+    /// Other regions of the document which are related to the problem.
+    pub secondary: Vec<Label>,
+    /// A suggestion for how to fix the problem.
+    pub help: Option<String>,
+}
+
+impl Diagnostic {
+    /// Creates a diagnostic with [`Severity::Error`].
+    #[must_use]
+    pub fn error(message: impl Into<String>, span: Span) -> Self {
+        Self {
+            severity: Severity::Error,
+            message: message.into(),
+            span,
+            label: None,
+            secondary: vec![],
+            help: None,
+        }
+    }
+
+    /// Creates a diagnostic with [`Severity::Warning`].
+    #[must_use]
+    pub fn warning(message: impl Into<String>, span: Span) -> Self {
+        Self {
+            severity: Severity::Warning,
+            message: message.into(),
+            span,
+            label: None,
+            secondary: vec![],
+            help: None,
+        }
+    }
+}
+
+impl Diagnostic {
+    /// Creates a diagnostic at the location described by `context`.
+    pub(crate) fn from_context(severity: Severity, message: String, context: &Context) -> Self {
+        Self {
+            severity,
+            message,
+            span: context.span,
+            label: context.label.clone(),
+            secondary: context.secondary.clone(),
+            help: context.help.clone(),
+        }
+    }
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {} at {}", self.severity, self.message, self.span)
+    }
+}
+
+/// Parses the input string and returns every problem found in it,
+/// in the order they appear.
+///
+/// A syntax error causes the pair, array element or assignment containing it to be skipped,
+/// after which parsing continues.
+/// Errors which would stop [`crate::parse`] once the syntax is valid,
+/// such as undefined inputs, are reported with the same details as
+/// [`Error::diagnostic`](crate::error::Error::diagnostic).
+///
+/// An empty list means the input would parse successfully.
+///
+/// # Examples
+///
+/// ```rust
+/// use corn::parse_all_diagnostics;
+///
+/// let corn = "{
+///     port = = 8080
+///     host = $undefined
+///     name = \"api\"
+/// }";
+///
+/// let diagnostics = parse_all_diagnostics(corn);
+/// assert_eq!(diagnostics.len(), 2);
+///
+/// assert_eq!(diagnostics[0].span.start.line, 2);
+/// assert_eq!(diagnostics[1].span.start.line, 3);
+/// assert_eq!(
+///     diagnostics[1].message,
+///     "failed to resolve referenced input `$undefined`"
+/// );
+/// ```
+#[must_use]
+pub fn parse_all_diagnostics(file: &str) -> Vec<Diagnostic> {
+    collect_diagnostics(file, vec![], &ParseOptions::default())
+}
+
+/// Collects every diagnostic for the input string.
+///
+/// Syntax errors are found by repeatedly parsing the input,
+/// repairing the region around each error reported in turn.
+/// Repairs never move any text, so spans found in the repaired input
+/// still point at the same parts of the original.
+pub(crate) fn collect_diagnostics(
+    file: &str,
+    import_chain: Vec<PathBuf>,
+    options: &ParseOptions,
+) -> Vec<Diagnostic> {
+    let mut diagnostics: Vec<Diagnostic> = vec![];
+
+    let mut repaired = file.to_string();
+    let mut repairs = find_repairs(file).unwrap_or_default();
+
+    loop {
+        match diagnose_in_chain(&repaired, import_chain.clone(), options) {
+            Ok(found) => {
+                diagnostics.extend(found);
+                break;
+            }
+            Err(error) => {
+                let diagnostic = syntax_diagnostic(&error);
+                let offset = diagnostic.span.start.offset;
+
+                if !diagnostics.contains(&diagnostic) {
+                    diagnostics.push(diagnostic);
+                }
+
+                match take_repair(&mut repairs, offset) {
+                    Some(Repair::Blank(range)) => {
+                        let blank = repaired[range.clone()]
+                            .chars()
+                            .map(|c| match c {
+                                '\n' => String::from("\n"),
+                                c => " ".repeat(c.len_utf8()),
+                            })
+                            .collect::<String>();
+
+                        repaired.replace_range(range, &blank);
+                    }
+                    // closing brackets can only be added at the end without moving any text
+                    Some(Repair::Insert(offset, text)) if offset == file.len() => {
+                        repaired.push_str(text);
+                    }
+                    Some(Repair::Insert(..)) | None => break,
+                }
+            }
+        }
+    }
+
+    // spans may cover closing brackets added to the end of the repaired input
+    let line_index = LineIndex::new(file);
+    for diagnostic in &mut diagnostics {
+        for position in [&mut diagnostic.span.start, &mut diagnostic.span.end] {
+            if position.offset > file.len() {
+                *position = line_index.position(file.len());
+            }
+        }
+    }
+
+    diagnostics.sort_by_key(|diagnostic| diagnostic.span.start.offset);
+    diagnostics
+}
+
+/// Converts a syntax error into a diagnostic.
+pub(crate) fn syntax_diagnostic(error: &pest::error::Error<Rule>) -> Diagnostic {
+    let (start, end) = match error.location {
+        InputLocation::Pos(pos) => (pos, pos),
+        InputLocation::Span(span) => span,
+    };
+
+    let (start_line_col, end_line_col) = match error.line_col {
+        LineColLocation::Pos(line_col) => (line_col, line_col),
+        LineColLocation::Span(start, end) => (start, end),
+    };
+
+    let position = |offset, (line, column)| Position {
+        offset,
+        line,
+        column,
+    };
+
+    Diagnostic::error(
+        error.variant.message(),
+        Span {
+            start: position(start, start_line_col),
+            end: position(end, end_line_col),
+        },
+    )
+}
+
+/// Removes and returns the repair for the error found at `offset`.
+///
+/// This is the last repair starting at or before the error,
+/// preferring the innermost where several start at the same offset.
+fn take_repair(repairs: &mut Vec<Repair>, offset: usize) -> Option<Repair> {
+    if repairs.is_empty() {
+        return None;
+    }
+
+    let index = repairs
+        .iter()
+        .enumerate()
+        .filter(|(_, repair)| repair.start() <= offset)
+        .fold(
+            None,
+            |found: Option<(usize, usize)>, (index, repair)| match found {
+                Some((_, start)) if start >= repair.start() => found,
+                _ => Some((index, repair.start())),
+            },
+        )
+        .map_or(0, |(index, _)| index);
+
+    Some(repairs.remove(index))
+}
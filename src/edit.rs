@@ -0,0 +1,660 @@
+//! Format-preserving editing of Corn documents.
+//!
+//! [`DocumentMut`] wraps a lossless [`cst`] tree,
+//! allowing values to be changed from code
+//! while keeping the comments and layout of the rest of the file intact.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use corn::DocumentMut;
+//!
+//! let input = "{
+//!     // the port to listen on
+//!     config.port = 8080
+//!
+//!     dependencies = {
+//!         dotenv = \"^8.2.0\"
+//!     }
+//! }";
+//!
+//! let mut document: DocumentMut = input.parse().unwrap();
+//!
+//! document.set("config.port", &9090).unwrap();
+//! document.set("dependencies.typescript", "^4.5").unwrap();
+//!
+//! assert_eq!(
+//!     document.to_string(),
+//!     "{
+//!     // the port to listen on
+//!     config.port = 9090
+//!
+//!     dependencies = {
+//!         dotenv = \"^8.2.0\"
+//!         typescript = \"^4.5\"
+//!     }
+//! }"
+//! );
+//! ```
+
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use serde::Serialize;
+
+use crate::cst::{self, Fragment, SyntaxElement, SyntaxKind, SyntaxNode, SyntaxToken};
+use crate::error::{Error, Result};
+use crate::parser::parse_key_path;
+use crate::ser::{format_key, value_to_string, ValueSerializer, DEFAULT_INDENT};
+use crate::Value;
+
+/// A mutable Corn document which preserves formatting when edited.
+///
+/// Paths use the same dot-notation as keys inside a document,
+/// including quoted segments such as `foo.'bar.baz'`.
+#[derive(Debug, Clone)]
+pub struct DocumentMut {
+    root: SyntaxNode,
+    /// The string used for a single level of indentation,
+    /// inferred from the existing document.
+    indent: String,
+}
+
+/// Where a path resolves to inside an object.
+enum Target {
+    /// Child indices leading to a pair which sets the path.
+    Pair(Vec<usize>),
+    /// Child indices leading to the object
+    /// which the remaining keys should be added to.
+    Insert(Vec<usize>, Vec<String>),
+}
+
+impl DocumentMut {
+    /// Parses `input` into an editable document.
+    ///
+    /// # Errors
+    ///
+    /// Will fail if the input contains a syntax error.
+    pub fn parse(input: &str) -> Result<Self> {
+        let root = cst::parse(input)?;
+        let indent = infer_indent(&root);
+
+        Ok(Self { root, indent })
+    }
+
+    /// The underlying syntax tree.
+    #[must_use]
+    pub fn as_syntax(&self) -> &SyntaxNode {
+        &self.root
+    }
+
+    /// Evaluates the document and gets the value at `path`,
+    /// or `None` if nothing is set there.
+    ///
+    /// # Errors
+    ///
+    /// Will fail if `path` is invalid,
+    /// or if the document fails to evaluate.
+    pub fn get(&self, path: &str) -> Result<Option<Value<'static>>> {
+        let keys = parse_key_path(path)?;
+
+        let input = self.to_string();
+        let mut value = crate::parse(&input)?;
+
+        for key in keys {
+            value = match value {
+                Value::Object(mut obj) => match obj.shift_remove(key.as_ref()) {
+                    Some(value) => value,
+                    None => return Ok(None),
+                },
+                _ => return Ok(None),
+            };
+        }
+
+        Ok(Some(value.into_owned()))
+    }
+
+    /// Sets the value at `path`.
+    ///
+    /// If a pair already sets `path`, its value is replaced in place.
+    /// Otherwise, a new pair is added to the deepest object literal
+    /// already containing part of the path.
+    /// Any pairs which set keys below `path` are removed.
+    ///
+    /// # Errors
+    ///
+    /// Will fail if `path` is invalid,
+    /// if part of `path` is already set to a non-object value,
+    /// or if `value` cannot be serialized.
+    pub fn set<T>(&mut self, path: &str, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        let keys = parse_owned_path(path)?;
+        let value = value.serialize(ValueSerializer)?;
+
+        let indent = self.indent.clone();
+
+        // changes are made to a copy, so the document is left untouched if any step fails
+        let mut root_object = self.root_object_mut().clone();
+        let object = &mut root_object;
+
+        remove_pairs(object, &keys, false);
+
+        match find_target(object, &keys, path, vec![])? {
+            Target::Pair(location) => {
+                let (index, parent) = location.split_last().expect("location should not be empty");
+                let parent = node_at_mut(object, parent);
+
+                let line_indent = line_indent(parent.children(), *index);
+                let element = render_value(&value, line_indent.as_deref(), &indent)?;
+
+                let pair = node_at_mut(parent, &[*index]);
+                *pair
+                    .children_mut()
+                    .last_mut()
+                    .expect("pairs should contain a value") = element;
+            }
+            Target::Insert(location, keys) => {
+                let path = keys
+                    .iter()
+                    .map(|key| format_key(key))
+                    .collect::<Result<Vec<_>>>()?
+                    .join(".");
+
+                let object = node_at_mut(object, &location);
+                insert_entry(
+                    object,
+                    SyntaxKind::LBrace,
+                    SyntaxKind::RBrace,
+                    |line_indent| {
+                        let value = render_value_text(&value, line_indent, &indent)?;
+                        cst::parse_fragment(Fragment::Pair, &format!("{path} = {value}"))
+                    },
+                )?;
+            }
+        }
+
+        *self.root_object_mut() = root_object;
+        Ok(())
+    }
+
+    /// Removes every pair which sets `path`, or any key below it.
+    /// Returns whether anything was removed.
+    ///
+    /// # Errors
+    ///
+    /// Will fail if `path` is invalid.
+    pub fn remove(&mut self, path: &str) -> Result<bool> {
+        let keys = parse_owned_path(path)?;
+        Ok(remove_pairs(self.root_object_mut(), &keys, true))
+    }
+
+    /// Appends `value` to the array literal at `path`.
+    ///
+    /// # Errors
+    ///
+    /// Will fail if `path` is invalid,
+    /// if `path` is not set to an array literal,
+    /// or if `value` cannot be serialized.
+    pub fn push<T>(&mut self, path: &str, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        let keys = parse_owned_path(path)?;
+        let value = value.serialize(ValueSerializer)?;
+
+        let indent = self.indent.clone();
+        let object = self.root_object_mut();
+
+        let not_array = || Error::EditError(format!("value at `{path}` is not an array literal"));
+
+        let Target::Pair(location) = find_target(object, &keys, path, vec![])? else {
+            return Err(not_array());
+        };
+
+        let pair = node_at_mut(object, &location);
+        let array = match pair.children_mut().last_mut() {
+            Some(SyntaxElement::Node(array)) if array.kind() == SyntaxKind::Array => array,
+            _ => return Err(not_array()),
+        };
+
+        insert_entry(
+            array,
+            SyntaxKind::LBracket,
+            SyntaxKind::RBracket,
+            |line_indent| render_value(&value, line_indent, &indent),
+        )
+    }
+
+    /// Sets the value of the input `name` inside the `let` block,
+    /// creating the block if it does not exist.
+    ///
+    /// `name` must include its `$` prefix.
+    ///
+    /// # Errors
+    ///
+    /// Will fail if `name` is not a valid input name,
+    /// or if `value` cannot be serialized.
+    pub fn set_input<T>(&mut self, name: &str, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        match cst::parse_fragment(Fragment::Value, name) {
+            Ok(SyntaxElement::Token(token)) if token.kind() == SyntaxKind::Input => {}
+            _ => {
+                return Err(Error::EditError(format!(
+                    "`{name}` is not a valid input name"
+                )))
+            }
+        }
+
+        let value = value.serialize(ValueSerializer)?;
+        let indent = self.indent.clone();
+
+        let Some(block_index) = self
+            .root
+            .children()
+            .iter()
+            .position(|child| child.kind() == SyntaxKind::AssignBlock)
+        else {
+            let value = render_value_text(&value, Some(&indent), &indent)?;
+            let block = cst::parse_fragment(
+                Fragment::AssignBlock,
+                &format!("let {{\n{indent}{name} = {value}\n}} in"),
+            )?;
+
+            let object_index = self
+                .root
+                .children()
+                .iter()
+                .position(|child| child.kind() == SyntaxKind::Object)
+                .expect("documents should contain an object");
+
+            self.root
+                .children_mut()
+                .splice(object_index..object_index, [block, whitespace(" ")]);
+
+            return Ok(());
+        };
+
+        let block = node_at_mut(&mut self.root, &[block_index]);
+
+        let existing = block.children().iter().rposition(|child| {
+            child
+                .as_node()
+                .is_some_and(|node| assignment_name(node) == Some(name))
+        });
+
+        if let Some(index) = existing {
+            let line_indent = line_indent(block.children(), index);
+            let element = render_value(&value, line_indent.as_deref(), &indent)?;
+
+            let assignment = node_at_mut(block, &[index]);
+            *assignment
+                .children_mut()
+                .last_mut()
+                .expect("assignments should contain a value") = element;
+
+            Ok(())
+        } else {
+            insert_entry(
+                block,
+                SyntaxKind::LBrace,
+                SyntaxKind::RBrace,
+                |line_indent| {
+                    let value = render_value_text(&value, line_indent, &indent)?;
+                    cst::parse_fragment(Fragment::Assignment, &format!("{name} = {value}"))
+                },
+            )
+        }
+    }
+
+    /// Removes the input `name` from the `let` block.
+    /// Returns whether anything was removed.
+    pub fn remove_input(&mut self, name: &str) -> bool {
+        let Some(block) = self
+            .root
+            .children_mut()
+            .iter_mut()
+            .find_map(|child| match child {
+                SyntaxElement::Node(node) if node.kind() == SyntaxKind::AssignBlock => Some(node),
+                _ => None,
+            })
+        else {
+            return false;
+        };
+
+        let mut removed = false;
+        for index in (0..block.children().len()).rev() {
+            let is_match = block.children()[index]
+                .as_node()
+                .is_some_and(|node| assignment_name(node) == Some(name));
+
+            if is_match {
+                remove_entry(block.children_mut(), index);
+                removed = true;
+            }
+        }
+
+        removed
+    }
+
+    fn root_object_mut(&mut self) -> &mut SyntaxNode {
+        self.root
+            .children_mut()
+            .iter_mut()
+            .find_map(|child| match child {
+                SyntaxElement::Node(node) if node.kind() == SyntaxKind::Object => Some(node),
+                _ => None,
+            })
+            .expect("documents should contain an object")
+    }
+}
+
+impl FromStr for DocumentMut {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::parse(s)
+    }
+}
+
+impl Display for DocumentMut {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.root.fmt(f)
+    }
+}
+
+fn parse_owned_path(path: &str) -> Result<Vec<String>> {
+    Ok(parse_key_path(path)?
+        .into_iter()
+        .map(|key| key.into_owned())
+        .collect())
+}
+
+fn whitespace(text: &str) -> SyntaxElement {
+    SyntaxElement::Token(SyntaxToken::new(SyntaxKind::Whitespace, text))
+}
+
+fn is_newline_whitespace(element: &SyntaxElement) -> bool {
+    element
+        .as_token()
+        .is_some_and(|token| token.kind() == SyntaxKind::Whitespace && token.text().contains('\n'))
+}
+
+/// Gets the indentation used by the first pair of the top-level object,
+/// falling back to four spaces.
+fn infer_indent(root: &SyntaxNode) -> String {
+    root.child_node(SyntaxKind::Object)
+        .and_then(|object| {
+            object
+                .children()
+                .windows(2)
+                .find(|pair| is_newline_whitespace(&pair[0]) && pair[1].kind() == SyntaxKind::Pair)
+                .map(|pair| pair[0].to_string())
+        })
+        .and_then(|whitespace| {
+            let indent = whitespace.rsplit('\n').next().unwrap_or_default();
+            (!indent.is_empty()).then(|| indent.to_string())
+        })
+        .unwrap_or_else(|| DEFAULT_INDENT.to_string())
+}
+
+/// Gets the keys of a `Pair` node's path.
+fn path_segments(pair: &SyntaxNode) -> Vec<String> {
+    pair.child_node(SyntaxKind::Path)
+        .expect("pairs should contain a path")
+        .child_tokens()
+        .filter_map(|token| match token.kind() {
+            SyntaxKind::PathSegment => Some(token.text().to_string()),
+            SyntaxKind::QuotedPathSegment => {
+                let text = token.text();
+                Some(text[1..text.len() - 1].replace('\\', ""))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Gets the name of an `Assignment` node's input.
+fn assignment_name(node: &SyntaxNode) -> Option<&str> {
+    if node.kind() != SyntaxKind::Assignment {
+        return None;
+    }
+
+    node.child_token(SyntaxKind::Input).map(SyntaxToken::text)
+}
+
+fn node_at_mut<'a>(mut node: &'a mut SyntaxNode, location: &[usize]) -> &'a mut SyntaxNode {
+    for index in location {
+        node = match &mut node.children_mut()[*index] {
+            SyntaxElement::Node(child) => child,
+            SyntaxElement::Token(_) => unreachable!("locations should only contain nodes"),
+        };
+    }
+
+    node
+}
+
+/// Finds the last pair in `object` which either sets `keys`,
+/// or sets an object literal which `keys` can be found inside.
+fn find_target(
+    object: &SyntaxNode,
+    keys: &[String],
+    path: &str,
+    mut location: Vec<usize>,
+) -> Result<Target> {
+    for (index, child) in object.children().iter().enumerate().rev() {
+        let Some(pair) = child
+            .as_node()
+            .filter(|node| node.kind() == SyntaxKind::Pair)
+        else {
+            continue;
+        };
+
+        let segments = path_segments(pair);
+
+        if segments == keys {
+            location.push(index);
+            return Ok(Target::Pair(location));
+        }
+
+        if keys.starts_with(&segments) {
+            let value_index = pair.children().len() - 1;
+
+            return match &pair.children()[value_index] {
+                SyntaxElement::Node(value) if value.kind() == SyntaxKind::Object => {
+                    location.extend([index, value_index]);
+                    find_target(value, &keys[segments.len()..], path, location)
+                }
+                // inputs may resolve to objects, so keys can be added after them
+                SyntaxElement::Token(value) if value.kind() == SyntaxKind::Input => {
+                    Ok(Target::Insert(location, keys.to_vec()))
+                }
+                _ => Err(Error::InvalidPathError(path.to_string(), None)),
+            };
+        }
+    }
+
+    Ok(Target::Insert(location, keys.to_vec()))
+}
+
+/// Removes pairs from `object` which set keys below `keys`,
+/// and pairs which set `keys` itself if `include_exact` is set.
+/// Object literals along the path are searched recursively.
+fn remove_pairs(object: &mut SyntaxNode, keys: &[String], include_exact: bool) -> bool {
+    let mut removed = false;
+
+    for index in (0..object.children().len()).rev() {
+        let segments = match &object.children()[index] {
+            SyntaxElement::Node(pair) if pair.kind() == SyntaxKind::Pair => path_segments(pair),
+            _ => continue,
+        };
+
+        let is_below = segments.len() > keys.len() && segments.starts_with(keys);
+
+        if is_below || (include_exact && segments == keys) {
+            remove_entry(object.children_mut(), index);
+            removed = true;
+        } else if segments.len() < keys.len() && keys.starts_with(&segments) {
+            let pair = node_at_mut(object, &[index]);
+            if let Some(SyntaxElement::Node(value)) = pair.children_mut().last_mut() {
+                if value.kind() == SyntaxKind::Object {
+                    removed |= remove_pairs(value, &keys[segments.len()..], include_exact);
+                }
+            }
+        }
+    }
+
+    removed
+}
+
+/// Removes the entry at `index`,
+/// along with the whitespace before it and any comment on the same line after it.
+fn remove_entry(children: &mut Vec<SyntaxElement>, index: usize) {
+    let mut end = index + 1;
+
+    let trailing_comment = children.get(end).is_some_and(|child| {
+        child.kind() == SyntaxKind::Whitespace && !is_newline_whitespace(child)
+    }) && children
+        .get(end + 1)
+        .is_some_and(|child| child.kind() == SyntaxKind::Comment);
+
+    if trailing_comment {
+        end += 2;
+    }
+
+    // only remove the preceding whitespace if something else still separates
+    // the surrounding elements.
+    let separated = children.get(end).is_some_and(|child| {
+        matches!(
+            child.kind(),
+            SyntaxKind::Whitespace | SyntaxKind::RBrace | SyntaxKind::RBracket
+        )
+    });
+
+    let start = if separated && index > 0 && children[index - 1].kind() == SyntaxKind::Whitespace {
+        index - 1
+    } else {
+        index
+    };
+
+    children.drain(start..end);
+}
+
+/// Gets the indentation of the line the child at `index` starts on,
+/// or `None` if it does not start its own line.
+fn line_indent(children: &[SyntaxElement], index: usize) -> Option<String> {
+    let previous = children.get(index.checked_sub(1)?)?;
+
+    if is_newline_whitespace(previous) {
+        let text = previous.to_string();
+        Some(text.rsplit('\n').next().unwrap_or_default().to_string())
+    } else {
+        None
+    }
+}
+
+/// Writes `value` as Corn text.
+/// If `line_indent` is set, objects and arrays are written across multiple lines
+/// relative to it.
+fn render_value_text(value: &Value, line_indent: Option<&str>, indent: &str) -> Result<String> {
+    match line_indent {
+        Some(line_indent) => {
+            Ok(value_to_string(value, Some(indent))?.replace('\n', &format!("\n{line_indent}")))
+        }
+        None => value_to_string(value, None),
+    }
+}
+
+fn render_value(value: &Value, line_indent: Option<&str>, indent: &str) -> Result<SyntaxElement> {
+    let text = render_value_text(value, line_indent, indent)?;
+    cst::parse_fragment(Fragment::Value, &text)
+}
+
+/// Adds a new entry to the end of an object, array or `let` block,
+/// matching the layout of existing entries.
+///
+/// `build` is called with the indentation of the line the entry will start on,
+/// or `None` if the container is on a single line.
+fn insert_entry(
+    container: &mut SyntaxNode,
+    open: SyntaxKind,
+    close: SyntaxKind,
+    build: impl FnOnce(Option<&str>) -> Result<SyntaxElement>,
+) -> Result<()> {
+    let children = container.children();
+
+    let open_index = children
+        .iter()
+        .position(|child| child.kind() == open)
+        .expect("containers should have an opening token");
+    let close_index = children
+        .iter()
+        .rposition(|child| child.kind() == close)
+        .expect("containers should have a closing token");
+
+    let inner = &children[open_index + 1..close_index];
+
+    let multiline = inner.iter().any(is_newline_whitespace);
+    let is_empty = inner.iter().all(|child| child.kind().is_trivia());
+
+    let closing_whitespace = (close_index > open_index + 1
+        && children[close_index - 1].kind() == SyntaxKind::Whitespace)
+        .then(|| children[close_index - 1].to_string());
+
+    let insert_index = if closing_whitespace.is_some() {
+        close_index - 1
+    } else {
+        close_index
+    };
+
+    let new_children = if multiline {
+        let entry_indent = inner
+            .windows(2)
+            .rev()
+            .find(|pair| is_newline_whitespace(&pair[0]))
+            .map(|pair| pair[0].to_string())
+            .map(|whitespace| {
+                whitespace
+                    .rsplit('\n')
+                    .next()
+                    .unwrap_or_default()
+                    .to_string()
+            });
+
+        let entry_indent = entry_indent.unwrap_or_else(|| {
+            let closing_indent = closing_whitespace
+                .as_deref()
+                .and_then(|whitespace| whitespace.rsplit('\n').next())
+                .unwrap_or_default();
+
+            format!("{closing_indent}{DEFAULT_INDENT}")
+        });
+
+        let element = build(Some(&entry_indent))?;
+        vec![whitespace(&format!("\n{entry_indent}")), element]
+    } else {
+        let element = build(None)?;
+
+        match (is_empty, closing_whitespace.is_some()) {
+            (true, true) => vec![whitespace(" "), element],
+            (true, false) => vec![whitespace(" "), element, whitespace(" ")],
+            (false, _) => vec![whitespace(" "), element],
+        }
+    };
+
+    // an empty container with closing whitespace keeps it after the new entry
+    let insert_index = if is_empty && !multiline && closing_whitespace.is_some() {
+        close_index - 1
+    } else {
+        insert_index
+    };
+
+    container
+        .children_mut()
+        .splice(insert_index..insert_index, new_children);
+
+    Ok(())
+}
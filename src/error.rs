@@ -1,10 +1,71 @@
 use std::fmt::{Debug, Display};
 use thiserror::Error;
 
+use crate::diagnostic::{syntax_diagnostic, Diagnostic, Label, Severity};
+use crate::span::Span;
 use crate::Rule;
 
 pub type Result<T> = std::result::Result<T, Error>;
 
+/// Where in the source input an error occurred,
+/// along with details to help fix it.
+///
+/// Errors returned by an [`InputResolver`](crate::InputResolver)
+/// do not need a context, as it is replaced with the location of the input reference.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Context {
+    /// The location of the problem.
+    pub span: Span,
+    /// Describes the problem at `span`.
+    pub label: Option<String>,
+    /// Other locations which are related to the problem.
+    pub secondary: Vec<Label>,
+    /// A suggestion for how to fix the problem.
+    pub help: Option<String>,
+}
+
+impl Context {
+    #[must_use]
+    pub fn new(span: Span) -> Self {
+        Self {
+            span,
+            label: None,
+            secondary: vec![],
+            help: None,
+        }
+    }
+
+    /// Sets the label shown at the location of the problem.
+    #[must_use]
+    pub fn label(mut self, label: impl Into<String>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    /// Adds a label at a related location.
+    #[must_use]
+    pub fn secondary(mut self, span: Span, message: impl Into<String>) -> Self {
+        self.secondary.push(Label {
+            span,
+            message: message.into(),
+        });
+        self
+    }
+
+    /// Sets a suggestion for how to fix the problem.
+    #[must_use]
+    pub fn help(mut self, help: impl Into<String>) -> Self {
+        self.help = Some(help.into());
+        self
+    }
+}
+
+impl From<Context> for Option<Box<Context>> {
+    fn from(context: Context) -> Self {
+        Some(Box::new(context))
+    }
+}
+
 #[derive(Error, Debug)]
 pub enum Error {
     #[error(transparent)]
@@ -14,19 +75,135 @@ pub enum Error {
     ParserError(#[from] Box<pest::error::Error<Rule>>),
 
     #[error("failed to resolve referenced input `{0}`")]
-    InputResolveError(String),
+    InputResolveError(String, Option<Box<Context>>),
+
+    #[error("required input `{input}` is not set: {message}")]
+    RequiredInputError {
+        /// The name of the input, including its `$` prefix.
+        input: String,
+        /// The message given alongside the input.
+        message: String,
+        context: Option<Box<Context>>,
+    },
+
+    #[error("access to environment variable `{0}` is not allowed")]
+    EnvAccessDeniedError(String, Option<Box<Context>>),
 
     #[error("attempted to use dot-notation on non-object value at `{0}`")]
-    InvalidPathError(String),
+    InvalidPathError(String, Option<Box<Context>>),
+
+    #[error("key `{0}` is set more than once")]
+    DuplicateKeyError(String, Option<Box<Context>>),
 
     #[error("attempted to spread a type that differs from its containing type at `{0}`")]
-    InvalidSpreadError(String),
+    InvalidSpreadError(String, Option<Box<Context>>),
 
     #[error("attempted to interpolate a non-string type into a string at `{0}`")]
-    InvalidInterpolationError(String),
+    InvalidInterpolationError(String, Option<Box<Context>>),
+
+    #[error("failed to convert input {0}")]
+    InvalidCastError(String, Option<Box<Context>>),
+
+    #[error("integer `{0}` is out of range")]
+    IntegerOverflowError(String, Option<Box<Context>>),
+
+    #[error("float `{0}` is out of range")]
+    FloatOverflowError(String, Option<Box<Context>>),
+
+    #[error("failed to import file: {0}")]
+    ImportError(String, Option<Box<Context>>),
 
-    #[error("failed to deserialize input: {0}")]
-    DeserializationError(String),
+    #[error("import cycle detected: {0}")]
+    ImportCycleError(String, Option<Box<Context>>),
+
+    #[error("failed to deserialize input: {message}{}", at_path(.path.as_deref()))]
+    DeserializationError {
+        message: String,
+        /// The location of the value which failed to deserialize,
+        /// such as `server.listeners[2].port`.
+        /// Not set if the root value failed to deserialize.
+        path: Option<String>,
+        context: Option<Box<Context>>,
+    },
+
+    #[error("failed to serialize value: {0}")]
+    SerializationError(String),
+
+    #[error("failed to edit document: {0}")]
+    EditError(String),
+}
+
+impl Error {
+    /// Gets the location in the source input the error occurred at, if known.
+    #[must_use]
+    pub fn context(&self) -> Option<&Context> {
+        match self {
+            Error::InputResolveError(_, context)
+            | Error::EnvAccessDeniedError(_, context)
+            | Error::InvalidPathError(_, context)
+            | Error::DuplicateKeyError(_, context)
+            | Error::InvalidSpreadError(_, context)
+            | Error::InvalidInterpolationError(_, context)
+            | Error::InvalidCastError(_, context)
+            | Error::IntegerOverflowError(_, context)
+            | Error::FloatOverflowError(_, context)
+            | Error::ImportError(_, context)
+            | Error::ImportCycleError(_, context)
+            | Error::RequiredInputError { context, .. }
+            | Error::DeserializationError { context, .. } => context.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// Sets the location in the source input the error occurred at,
+    /// replacing any existing context.
+    ///
+    /// Has no effect on errors which do not come from the source input,
+    /// such as [`Error::Io`].
+    #[must_use]
+    pub fn with_context(mut self, new_context: Context) -> Self {
+        match &mut self {
+            Error::InputResolveError(_, context)
+            | Error::EnvAccessDeniedError(_, context)
+            | Error::InvalidPathError(_, context)
+            | Error::DuplicateKeyError(_, context)
+            | Error::InvalidSpreadError(_, context)
+            | Error::InvalidInterpolationError(_, context)
+            | Error::InvalidCastError(_, context)
+            | Error::IntegerOverflowError(_, context)
+            | Error::FloatOverflowError(_, context)
+            | Error::ImportError(_, context)
+            | Error::ImportCycleError(_, context)
+            | Error::RequiredInputError { context, .. }
+            | Error::DeserializationError { context, .. } => *context = new_context.into(),
+            _ => {}
+        }
+
+        self
+    }
+
+    /// Converts the error into a diagnostic, if its location in the source input is known.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use corn::parse;
+    ///
+    /// let corn = "{ foo = $foo }";
+    ///
+    /// let diagnostic = parse(corn).unwrap_err().diagnostic().unwrap();
+    /// assert_eq!(&corn[diagnostic.span.range()], "$foo");
+    /// assert_eq!(diagnostic.label.as_deref(), Some("not defined"));
+    /// ```
+    #[must_use]
+    pub fn diagnostic(&self) -> Option<Diagnostic> {
+        if let Error::ParserError(error) = self {
+            return Some(syntax_diagnostic(error));
+        }
+
+        self.context()
+            .map(|context| Diagnostic::from_context(Severity::Error, self.to_string(), context))
+    }
 }
 
 impl serde::de::Error for Error {
@@ -34,6 +211,24 @@ impl serde::de::Error for Error {
     where
         T: Display,
     {
-        Error::DeserializationError(msg.to_string())
+        Error::DeserializationError {
+            message: msg.to_string(),
+            path: None,
+            context: None,
+        }
+    }
+}
+
+impl serde::ser::Error for Error {
+    fn custom<T>(msg: T) -> Self
+    where
+        T: Display,
+    {
+        Error::SerializationError(msg.to_string())
     }
 }
+
+/// Formats the path of a deserialization error, if it has one.
+fn at_path(path: Option<&str>) -> String {
+    path.map(|path| format!(" at `{path}`")).unwrap_or_default()
+}
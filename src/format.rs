@@ -0,0 +1,288 @@
+use crate::cst::{self, SyntaxElement, SyntaxKind, SyntaxNode};
+use crate::error::Result;
+use crate::ser::DEFAULT_INDENT;
+
+/// Rewrites `input` in the canonical Corn style.
+///
+/// - Indentation uses four spaces per level.
+/// - Objects and `let` blocks place each entry on its own line.
+/// - Arrays stay on a single line unless they contain objects, arrays or comments.
+/// - Comments and single blank lines between entries are kept.
+///
+/// Literals and keys are written exactly as they appear in the input.
+///
+/// # Errors
+///
+/// Will fail if the input contains a syntax error.
+///
+/// # Examples
+///
+/// ```rust
+/// use corn::format;
+///
+/// let formatted = format("let{$foo=1}in{a=$foo // the answer\nb={c=[1 2]}}").unwrap();
+///
+/// assert_eq!(
+///     formatted,
+///     "let {\n    $foo = 1\n} in {\n    a = $foo // the answer\n    b = {\n        c = [ 1 2 ]\n    }\n}\n"
+/// );
+/// ```
+pub fn format(input: &str) -> Result<String> {
+    let root = cst::parse(input)?;
+
+    let mut formatter = Formatter::default();
+    formatter.write_document(&root);
+
+    Ok(formatter.output)
+}
+
+#[derive(Default)]
+struct Formatter {
+    output: String,
+}
+
+fn newline_count(element: &SyntaxElement) -> usize {
+    element
+        .as_token()
+        .filter(|token| token.kind() == SyntaxKind::Whitespace)
+        .map_or(0, |token| token.text().matches('\n').count())
+}
+
+impl Formatter {
+    fn write_indent(&mut self, level: usize) {
+        for _ in 0..level {
+            self.output.push_str(DEFAULT_INDENT);
+        }
+    }
+
+    fn write_newline(&mut self, level: usize, blank_line: bool) {
+        if blank_line {
+            self.output.push('\n');
+        }
+
+        self.output.push('\n');
+        self.write_indent(level);
+    }
+
+    fn write_document(&mut self, root: &SyntaxNode) {
+        let mut blank_line = false;
+        // whether the last thing written can be followed by a comment on the same line
+        let mut line_open = false;
+        // whether the last thing written was the `in` keyword
+        let mut after_block = false;
+
+        for child in root.children() {
+            match child.kind() {
+                SyntaxKind::Whitespace => {
+                    let newlines = newline_count(child);
+
+                    if newlines > 0 {
+                        line_open = false;
+                    }
+
+                    if newlines > 1 && !self.output.is_empty() {
+                        blank_line = true;
+                    }
+                }
+                SyntaxKind::Comment => {
+                    if line_open {
+                        self.output.push(' ');
+                    } else if !self.output.is_empty() {
+                        self.write_newline(0, blank_line);
+                    }
+
+                    self.output.push_str(&child.to_string());
+
+                    blank_line = false;
+                    line_open = false;
+                    after_block = false;
+                }
+                _ => {
+                    if after_block {
+                        self.output.push(' ');
+                    } else if !self.output.is_empty() {
+                        self.write_newline(0, blank_line);
+                    }
+
+                    self.write_element(child, 0);
+
+                    blank_line = false;
+                    line_open = true;
+                    after_block = child.kind() == SyntaxKind::AssignBlock;
+                }
+            }
+        }
+
+        self.output.push('\n');
+    }
+
+    fn write_element(&mut self, element: &SyntaxElement, level: usize) {
+        match element {
+            SyntaxElement::Token(token) => self.output.push_str(token.text()),
+            SyntaxElement::Node(node) => match node.kind() {
+                SyntaxKind::Object | SyntaxKind::Array => {
+                    let children = node.children();
+                    self.write_container(
+                        &children[0],
+                        &children[1..children.len() - 1],
+                        &children[children.len() - 1],
+                        level,
+                    );
+                }
+                SyntaxKind::Spread | SyntaxKind::Required => {
+                    self.write_sequence(node.children(), level, "")
+                }
+                SyntaxKind::Path => self.output.push_str(&node.to_string()),
+                _ => self.write_sequence(node.children(), level, " "),
+            },
+        }
+    }
+
+    /// Writes the children of a node on a single line, joined by `separator`.
+    /// Braces inside `let` blocks are written as a container.
+    fn write_sequence(&mut self, children: &[SyntaxElement], level: usize, separator: &str) {
+        let mut first = true;
+        let mut line_start = false;
+
+        let mut index = 0;
+        while index < children.len() {
+            let child = &children[index];
+
+            match child.kind() {
+                SyntaxKind::Whitespace => {}
+                SyntaxKind::Comment => {
+                    if !line_start {
+                        self.output.push(' ');
+                    }
+
+                    self.output.push_str(&child.to_string());
+
+                    // `in` closes the `let` block, so lines up with `let` rather than continuing it
+                    let before_in = children[index + 1..]
+                        .iter()
+                        .find(|child| !child.kind().is_trivia())
+                        .is_some_and(|child| child.kind() == SyntaxKind::InKeyword);
+
+                    self.write_newline(if before_in { level } else { level + 1 }, false);
+                    line_start = true;
+                }
+                kind => {
+                    if !first && !line_start {
+                        self.output.push_str(separator);
+                    }
+
+                    if kind == SyntaxKind::LBrace {
+                        let close = children
+                            .iter()
+                            .rposition(|child| child.kind() == SyntaxKind::RBrace)
+                            .expect("blocks should have a closing brace");
+
+                        self.write_container(
+                            child,
+                            &children[index + 1..close],
+                            &children[close],
+                            level,
+                        );
+                        index = close;
+                    } else {
+                        self.write_element(child, level);
+                    }
+
+                    first = false;
+                    line_start = false;
+                }
+            }
+
+            index += 1;
+        }
+    }
+
+    /// Writes an object, array or `let` block with its entries.
+    fn write_container(
+        &mut self,
+        open: &SyntaxElement,
+        inner: &[SyntaxElement],
+        close: &SyntaxElement,
+        level: usize,
+    ) {
+        let has_comments = inner
+            .iter()
+            .any(|child| child.kind() == SyntaxKind::Comment);
+        let mut entries = inner
+            .iter()
+            .filter(|child| !child.kind().is_trivia())
+            .peekable();
+
+        self.write_element(open, level);
+
+        if !has_comments && entries.peek().is_none() {
+            self.write_element(close, level);
+            return;
+        }
+
+        let inline = open.kind() == SyntaxKind::LBracket
+            && !has_comments
+            && inner
+                .iter()
+                .filter(|child| !child.kind().is_trivia())
+                .all(|child| match child {
+                    SyntaxElement::Token(token) => !token.text().contains('\n'),
+                    SyntaxElement::Node(node) => node.kind() == SyntaxKind::Spread,
+                });
+
+        if inline {
+            for entry in entries {
+                self.output.push(' ');
+                self.write_element(entry, level);
+            }
+
+            self.output.push(' ');
+            self.write_element(close, level);
+            return;
+        }
+
+        let mut first = true;
+        let mut blank_line = false;
+        let mut line_open = false;
+
+        for child in inner {
+            match child.kind() {
+                SyntaxKind::Whitespace => {
+                    let newlines = newline_count(child);
+
+                    if newlines > 0 {
+                        line_open = false;
+                    }
+
+                    if newlines > 1 && !first {
+                        blank_line = true;
+                    }
+                }
+                SyntaxKind::Comment => {
+                    if line_open {
+                        self.output.push(' ');
+                    } else {
+                        self.write_newline(level + 1, blank_line);
+                    }
+
+                    self.output.push_str(&child.to_string());
+
+                    first = false;
+                    blank_line = false;
+                    line_open = false;
+                }
+                _ => {
+                    self.write_newline(level + 1, blank_line);
+                    self.write_element(child, level + 1);
+
+                    first = false;
+                    blank_line = false;
+                    line_open = true;
+                }
+            }
+        }
+
+        self.write_newline(level, false);
+        self.write_element(close, level);
+    }
+}
@@ -4,11 +4,29 @@ use std::borrow::Cow;
 use std::collections::HashMap;
 use std::fmt::{Display, Formatter};
 
-pub use crate::de::{from_slice, from_str};
-pub use crate::parser::{parse, Rule};
+pub use crate::de::{from_slice, from_str, from_value, from_value_ref, Deserializer, UnknownKey};
+pub use crate::diagnostic::{parse_all_diagnostics, Diagnostic, Label, Severity};
+pub use crate::edit::DocumentMut;
+pub use crate::format::format;
+pub use crate::options::{
+    parse_with_inputs, DeserializerOptions, ImportAccess, InputPrecedence, ParseOptions,
+    UnknownKeys,
+};
+pub use crate::parser::{parse, parse_file, parse_spanned, parse_with_warnings, Rule};
+pub use crate::resolver::InputResolver;
+pub use crate::ser::{to_string, to_string_pretty, to_value, Serializer};
+pub use crate::span::{Position, Span, Spanned, SpannedEntry, SpannedObject, SpannedValue};
 
+pub mod cst;
+mod diagnostic;
+pub mod edit;
 pub mod error;
+mod format;
+mod options;
 mod parser;
+pub mod resolver;
+mod ser;
+mod span;
 
 mod de;
 #[cfg(any(
@@ -39,8 +57,18 @@ pub enum Value<'a> {
     Array(Vec<Value<'a>>),
     /// UTF-8 string
     String(Cow<'a, str>),
-    /// 64-bit signed integer.
-    Integer(i64),
+    /// 128-bit signed integer,
+    /// which can hold any 64-bit signed or unsigned integer.
+    ///
+    /// Values must be between `i128::MIN` and `i128::MAX`,
+    /// so `u128` values above `i128::MAX` cannot be represented
+    /// and fail to parse, serialize or deserialize.
+    ///
+    /// Serialized as the narrowest of `i64`, `u64` and `i128` which fits,
+    /// as many formats only support 64-bit integers.
+    /// Formats limited to 64-bit signed integers, such as TOML,
+    /// fail to serialize values outside that range.
+    Integer(#[serde(serialize_with = "serialize_integer")] i128),
     /// 64-bit (double precision) floating point number.
     Float(f64),
     /// true or false
@@ -53,6 +81,27 @@ pub enum Value<'a> {
     Null(Option<()>),
 }
 
+impl Value<'_> {
+    /// Converts into a value which owns all of its data,
+    /// so that it no longer borrows from the input it was parsed from.
+    #[must_use]
+    pub fn into_owned(self) -> Value<'static> {
+        match self {
+            Value::Object(obj) => Value::Object(
+                obj.into_iter()
+                    .map(|(key, value)| (Cow::Owned(key.into_owned()), value.into_owned()))
+                    .collect(),
+            ),
+            Value::Array(arr) => Value::Array(arr.into_iter().map(Value::into_owned).collect()),
+            Value::String(val) => Value::String(Cow::Owned(val.into_owned())),
+            Value::Integer(val) => Value::Integer(val),
+            Value::Float(val) => Value::Float(val),
+            Value::Boolean(val) => Value::Boolean(val),
+            Value::Null(val) => Value::Null(val),
+        }
+    }
+}
+
 impl Display for Value<'_> {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(
@@ -70,3 +119,65 @@ impl Display for Value<'_> {
         )
     }
 }
+
+impl<'a> From<&'a str> for Value<'a> {
+    fn from(value: &'a str) -> Self {
+        Value::String(Cow::Borrowed(value))
+    }
+}
+
+impl From<String> for Value<'_> {
+    fn from(value: String) -> Self {
+        Value::String(Cow::Owned(value))
+    }
+}
+
+impl From<i32> for Value<'_> {
+    fn from(value: i32) -> Self {
+        Value::Integer(i128::from(value))
+    }
+}
+
+impl From<i64> for Value<'_> {
+    fn from(value: i64) -> Self {
+        Value::Integer(i128::from(value))
+    }
+}
+
+impl From<u64> for Value<'_> {
+    fn from(value: u64) -> Self {
+        Value::Integer(i128::from(value))
+    }
+}
+
+impl From<i128> for Value<'_> {
+    fn from(value: i128) -> Self {
+        Value::Integer(value)
+    }
+}
+
+impl From<f64> for Value<'_> {
+    fn from(value: f64) -> Self {
+        Value::Float(value)
+    }
+}
+
+impl From<bool> for Value<'_> {
+    fn from(value: bool) -> Self {
+        Value::Boolean(value)
+    }
+}
+
+/// Serializes `value` as the narrowest of `i64`, `u64` and `i128` which can hold it.
+fn serialize_integer<S>(value: &i128, serializer: S) -> std::result::Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    if let Ok(value) = i64::try_from(*value) {
+        serializer.serialize_i64(value)
+    } else if let Ok(value) = u64::try_from(*value) {
+        serializer.serialize_u64(value)
+    } else {
+        serializer.serialize_i128(*value)
+    }
+}
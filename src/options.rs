@@ -0,0 +1,561 @@
+use std::fmt::{Debug, Formatter};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use serde::de::DeserializeOwned;
+
+use crate::de::{Deserializer, UnknownKey};
+use crate::diagnostic::{collect_diagnostics, Diagnostic};
+use crate::error::{Error, Result};
+use crate::parser::{parse_file_in_chain, parse_in_chain};
+use crate::resolver::{EnvAccess, EnvResolver, InputResolver};
+use crate::{Inputs, Spanned, SpannedValue, Value};
+
+/// Which value is used when an input is both supplied by the host
+/// and defined in the `let` block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum InputPrecedence {
+    /// Inputs supplied by the host override the `let` block,
+    /// which then acts as a set of defaults.
+    #[default]
+    Host,
+    /// Inputs defined in the `let` block override those supplied by the host,
+    /// which are then only used for inputs the file does not define.
+    Let,
+}
+
+/// Which files configs are allowed to import.
+///
+/// Strings parsed without a file, such as by [`crate::parse`], can never import files.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum ImportAccess {
+    /// Any file can be imported.
+    #[default]
+    All,
+    /// No files can be imported.
+    None,
+    /// Only files inside this directory, or its subdirectories, can be imported.
+    Within(PathBuf),
+}
+
+impl ImportAccess {
+    /// Whether the file at the canonical `path` can be imported.
+    pub(crate) fn allows(&self, path: &Path) -> bool {
+        match self {
+            Self::All => true,
+            Self::None => false,
+            Self::Within(dir) => dir.canonicalize().is_ok_and(|dir| path.starts_with(dir)),
+        }
+    }
+}
+
+/// Options for parsing Corn,
+/// allowing the host program to control how inputs are resolved.
+///
+/// # Examples
+///
+/// ```rust
+/// use corn::{InputPrecedence, ParseOptions, Value};
+///
+/// let corn = "let { $region = \"eu-west-1\" } in { region = $region build = $build_id }";
+///
+/// let options = ParseOptions::new()
+///     .input("$region", Value::from("us-east-1"))
+///     .input("$build_id", Value::Integer(1234));
+///
+/// let config = options.parse(corn).unwrap();
+/// let json = serde_json::to_string(&config).unwrap();
+/// assert_eq!(json, "{\"region\":\"us-east-1\",\"build\":1234}");
+///
+/// let config = options.precedence(InputPrecedence::Let).parse(corn).unwrap();
+/// let json = serde_json::to_string(&config).unwrap();
+/// assert_eq!(json, "{\"region\":\"eu-west-1\",\"build\":1234}");
+/// ```
+#[derive(Clone)]
+pub struct ParseOptions<'a> {
+    pub(crate) inputs: Inputs<'a>,
+    pub(crate) precedence: InputPrecedence,
+    pub(crate) strict: bool,
+    pub(crate) imports: ImportAccess,
+    resolvers: Vec<(String, Arc<dyn InputResolver>)>,
+}
+
+impl Default for ParseOptions<'_> {
+    fn default() -> Self {
+        Self {
+            inputs: Inputs::new(),
+            precedence: InputPrecedence::default(),
+            strict: false,
+            imports: ImportAccess::default(),
+            resolvers: vec![(String::from("$env_"), Arc::new(EnvResolver::new()))],
+        }
+    }
+}
+
+impl Debug for ParseOptions<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ParseOptions")
+            .field("inputs", &self.inputs)
+            .field("precedence", &self.precedence)
+            .field("strict", &self.strict)
+            .field("imports", &self.imports)
+            .field(
+                "resolvers",
+                &self
+                    .resolvers
+                    .iter()
+                    .map(|(prefix, _)| prefix)
+                    .collect::<Vec<_>>(),
+            )
+            .finish()
+    }
+}
+
+impl<'a> ParseOptions<'a> {
+    /// Creates options with no host inputs
+    /// and environment variables available through `$env_`,
+    /// matching the behaviour of [`crate::parse`].
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the inputs supplied by the host,
+    /// replacing any previously set.
+    /// Input names must include their `$` prefix.
+    #[must_use]
+    pub fn inputs(mut self, inputs: Inputs<'a>) -> Self {
+        self.inputs = inputs;
+        self
+    }
+
+    /// Adds a single input supplied by the host.
+    /// The name must include its `$` prefix.
+    #[must_use]
+    pub fn input(mut self, name: &'a str, value: Value<'a>) -> Self {
+        self.inputs.insert(name, value);
+        self
+    }
+
+    /// Sets which value is used when an input is both supplied by the host
+    /// and defined in the `let` block.
+    /// Defaults to [`InputPrecedence::Host`].
+    #[must_use]
+    pub fn precedence(mut self, precedence: InputPrecedence) -> Self {
+        self.precedence = precedence;
+        self
+    }
+
+    /// Sets whether setting the same key more than once is an error.
+    ///
+    /// When disabled, which is the default, the last value is used
+    /// and each duplicate is reported as a warning by [`ParseOptions::parse_with_warnings`].
+    /// When enabled, duplicates fail with
+    /// [`Error::DuplicateKeyError`](crate::error::Error::DuplicateKeyError).
+    ///
+    /// Each key from a spread, including nested keys, can be overridden once.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use corn::error::Error;
+    /// use corn::ParseOptions;
+    ///
+    /// let corn = "{ port = 8080 port = 9090 }";
+    ///
+    /// let config = ParseOptions::new().parse(corn).unwrap();
+    /// assert_eq!(serde_json::to_string(&config).unwrap(), "{\"port\":9090}");
+    ///
+    /// let err = ParseOptions::new().strict(true).parse(corn).unwrap_err();
+    /// assert!(matches!(err, Error::DuplicateKeyError(..)));
+    /// ```
+    #[must_use]
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Restricts which files can be imported by files parsed with these options.
+    ///
+    /// Importing a file which is not allowed fails with
+    /// [`Error::ImportError`](crate::error::Error::ImportError).
+    /// Defaults to [`ImportAccess::All`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use corn::{ImportAccess, ParseOptions};
+    ///
+    /// let options = ParseOptions::new().imports(ImportAccess::Within("/srv/tenants/acme".into()));
+    /// ```
+    #[must_use]
+    pub fn imports(mut self, access: ImportAccess) -> Self {
+        self.imports = access;
+        self
+    }
+
+    /// Registers `resolver` for inputs starting with `prefix`,
+    /// replacing any resolver already registered for the same prefix.
+    ///
+    /// Resolvers are consulted before host inputs and the `let` block.
+    /// If several prefixes match an input, the longest is used.
+    ///
+    /// To stop configs reading environment variables,
+    /// register a [`DenyResolver`](crate::resolver::DenyResolver) for `$env_`.
+    #[must_use]
+    pub fn resolver(
+        mut self,
+        prefix: impl Into<String>,
+        resolver: impl InputResolver + 'static,
+    ) -> Self {
+        let prefix = prefix.into();
+        self.resolvers.retain(|(existing, _)| existing != &prefix);
+        self.resolvers.push((prefix, Arc::new(resolver)));
+        self
+    }
+
+    /// Restricts which environment variables can be read through `$env_` inputs.
+    ///
+    /// Referencing a variable which is not allowed fails with
+    /// [`Error::EnvAccessDeniedError`](crate::error::Error::EnvAccessDeniedError),
+    /// unless the reference has a `??` fallback, which is used instead.
+    /// This replaces any resolver registered for `$env_`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use corn::error::Error;
+    /// use corn::resolver::EnvAccess;
+    /// use corn::ParseOptions;
+    ///
+    /// let options = ParseOptions::new().env_access(EnvAccess::Prefix(String::from("APP_")));
+    ///
+    /// let err = options.parse("{ key = $env_AWS_SECRET_ACCESS_KEY }").unwrap_err();
+    /// assert!(matches!(err, Error::EnvAccessDeniedError(..)));
+    /// ```
+    #[must_use]
+    pub fn env_access(self, access: EnvAccess) -> Self {
+        self.resolver("$env_", EnvResolver::with_access(access))
+    }
+
+    /// Gets the resolver registered for the longest prefix of `name`,
+    /// along with the rest of the name after that prefix.
+    pub(crate) fn resolver_for<'n>(&self, name: &'n str) -> Option<(&dyn InputResolver, &'n str)> {
+        self.resolvers
+            .iter()
+            .filter(|(prefix, _)| name.starts_with(prefix.as_str()))
+            .max_by_key(|(prefix, _)| prefix.len())
+            .map(|(prefix, resolver)| (resolver.as_ref(), &name[prefix.len()..]))
+    }
+
+    /// Parses the input string using these options.
+    ///
+    /// # Errors
+    ///
+    /// Fails under the same conditions as [`crate::parse`].
+    pub fn parse(&self, file: &'a str) -> Result<Value<'a>> {
+        self.parse_spanned(file).map(Value::from)
+    }
+
+    /// Parses the input string using these options,
+    /// keeping the source location of every key and value.
+    ///
+    /// Values of host inputs are attributed to the location they are referenced from.
+    ///
+    /// # Errors
+    ///
+    /// Fails under the same conditions as [`crate::parse`].
+    pub fn parse_spanned(&self, file: &'a str) -> Result<Spanned<SpannedValue<'a>>> {
+        parse_in_chain(file, vec![], self).map(|(value, _)| value)
+    }
+
+    /// Parses the input string using these options,
+    /// also returning any warnings.
+    ///
+    /// See [`crate::parse_with_warnings`] for details.
+    ///
+    /// # Errors
+    ///
+    /// Fails under the same conditions as [`crate::parse`].
+    pub fn parse_with_warnings(&self, file: &'a str) -> Result<(Value<'a>, Vec<Diagnostic>)> {
+        parse_in_chain(file, vec![], self).map(|(value, warnings)| (Value::from(value), warnings))
+    }
+
+    /// Parses the input string using these options,
+    /// returning every problem found in it.
+    ///
+    /// See [`crate::parse_all_diagnostics`] for details.
+    #[must_use]
+    pub fn parse_all_diagnostics(&self, file: &str) -> Vec<Diagnostic> {
+        collect_diagnostics(file, vec![], self)
+    }
+
+    /// Reads and parses the Corn file at `path` using these options.
+    /// The same options are used for any files it imports.
+    ///
+    /// # Errors
+    ///
+    /// Fails under the same conditions as [`crate::parse_file`].
+    pub fn parse_file(&self, path: impl AsRef<Path>) -> Result<Value<'static>> {
+        self.parse_file_with_warnings(path).map(|(value, _)| value)
+    }
+
+    /// Reads and parses the Corn file at `path` using these options,
+    /// also returning any warnings.
+    /// Warnings are only given for the file itself, not any files it imports.
+    ///
+    /// # Errors
+    ///
+    /// Fails under the same conditions as [`crate::parse_file`].
+    pub fn parse_file_with_warnings(
+        &self,
+        path: impl AsRef<Path>,
+    ) -> Result<(Value<'static>, Vec<Diagnostic>)> {
+        let path = path.as_ref().canonicalize()?;
+        parse_file_in_chain(&path, vec![], self)
+    }
+}
+
+/// Parses the input string in the same way as [`crate::parse`],
+/// using `inputs` supplied by the host alongside those defined in the `let` block.
+///
+/// Host inputs override inputs of the same name in the `let` block.
+/// To change this, use [`ParseOptions::precedence`].
+///
+/// # Examples
+///
+/// ```rust
+/// use corn::{parse_with_inputs, Inputs, Value};
+///
+/// let inputs = Inputs::from([("$region", Value::from("eu-west-1"))]);
+///
+/// let config = parse_with_inputs("{ region = $region }", inputs).unwrap();
+/// let json = serde_json::to_string(&config).unwrap();
+///
+/// assert_eq!(json, "{\"region\":\"eu-west-1\"}");
+/// ```
+///
+/// # Errors
+///
+/// Fails under the same conditions as [`crate::parse`].
+pub fn parse_with_inputs<'a>(file: &'a str, inputs: Inputs<'a>) -> Result<Value<'a>> {
+    ParseOptions::new().inputs(inputs).parse(file)
+}
+
+/// What happens to keys which are not used by the type being deserialized into.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum UnknownKeys {
+    /// Unknown keys are skipped.
+    /// They can be collected using [`DeserializerOptions::from_str_with_unknown_keys`].
+    #[default]
+    Ignore,
+    /// Unknown keys fail with a
+    /// [`Error::DeserializationError`](crate::error::Error::DeserializationError)
+    /// pointing at the key.
+    Deny,
+}
+
+/// Options for deserializing Corn into Rust types,
+/// allowing the host program to control how inputs are resolved
+/// and how values are converted.
+///
+/// # Examples
+///
+/// ```rust
+/// use corn::{DeserializerOptions, ParseOptions, UnknownKeys, Value};
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct Config {
+///     timeout: u32,
+///     region: String,
+/// }
+///
+/// let options = DeserializerOptions::new()
+///     .parse_options(ParseOptions::new().input("$region", Value::from("eu-west-1")))
+///     .float_to_int(true)
+///     .unknown_keys(UnknownKeys::Deny);
+///
+/// let config: Config = options.from_str("{ timeout = 30.0 region = $region }").unwrap();
+/// assert_eq!(config.timeout, 30);
+/// assert_eq!(config.region, "eu-west-1");
+///
+/// assert!(options.from_str::<Config>("{ timeout = 30.5 region = $region }").is_err());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct DeserializerOptions<'a> {
+    pub(crate) parse: ParseOptions<'a>,
+    pub(crate) conversion: ConversionOptions,
+}
+
+/// The options which control how each value is converted,
+/// passed down to the deserializer of every value in the document.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ConversionOptions {
+    pub(crate) int_to_float: bool,
+    pub(crate) float_to_int: bool,
+    pub(crate) unknown_keys: UnknownKeys,
+    pub(crate) strict_enums: bool,
+}
+
+impl Default for ConversionOptions {
+    fn default() -> Self {
+        Self {
+            int_to_float: true,
+            float_to_int: false,
+            unknown_keys: UnknownKeys::default(),
+            strict_enums: false,
+        }
+    }
+}
+
+impl<'a> DeserializerOptions<'a> {
+    /// Creates options matching the behaviour of [`crate::from_str`].
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the options used to parse the input,
+    /// such as host inputs and input resolvers.
+    /// Defaults to [`ParseOptions::new`].
+    #[must_use]
+    pub fn parse_options(mut self, parse: ParseOptions<'a>) -> Self {
+        self.parse = parse;
+        self
+    }
+
+    /// Sets whether integers can be deserialized into float types.
+    /// Enabled by default.
+    #[must_use]
+    pub fn int_to_float(mut self, int_to_float: bool) -> Self {
+        self.conversion.int_to_float = int_to_float;
+        self
+    }
+
+    /// Sets whether floats with no fractional part, such as `3.0`,
+    /// can be deserialized into integer types.
+    ///
+    /// Floats which would lose precision are still an error.
+    /// Disabled by default.
+    #[must_use]
+    pub fn float_to_int(mut self, float_to_int: bool) -> Self {
+        self.conversion.float_to_int = float_to_int;
+        self
+    }
+
+    /// Sets what happens to keys which are not used by the type being deserialized into,
+    /// such as a misspelled field name.
+    /// Defaults to [`UnknownKeys::Ignore`].
+    ///
+    /// Keys inside an unknown key are not checked.
+    /// Keys of structs using `#[serde(flatten)]` are never reported,
+    /// as all of their keys are passed to the flattened fields.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use corn::{DeserializerOptions, UnknownKeys};
+    /// use serde::Deserialize;
+    ///
+    /// #[derive(Deserialize)]
+    /// struct Server {
+    ///     port: Option<u16>,
+    /// }
+    ///
+    /// let options = DeserializerOptions::new().unknown_keys(UnknownKeys::Deny);
+    ///
+    /// let err = options.from_str::<Server>("{ prot = 8080 }").err().unwrap();
+    /// assert_eq!(
+    ///     err.to_string(),
+    ///     "failed to deserialize input: unknown key at `prot`"
+    /// );
+    /// ```
+    #[must_use]
+    pub fn unknown_keys(mut self, unknown_keys: UnknownKeys) -> Self {
+        self.conversion.unknown_keys = unknown_keys;
+        self
+    }
+
+    /// Sets whether an enum variant given as an object must have exactly one key,
+    /// such as `{ Tcp.port = 80 }`.
+    ///
+    /// When disabled, which is the default, any keys after the first are ignored.
+    #[must_use]
+    pub fn strict_enums(mut self, strict_enums: bool) -> Self {
+        self.conversion.strict_enums = strict_enums;
+        self
+    }
+
+    /// Deserializes the config from a string slice using these options.
+    ///
+    /// # Errors
+    ///
+    /// Fails under the same conditions as [`crate::from_str`].
+    pub fn from_str<T>(&self, s: &'a str) -> Result<T>
+    where
+        T: DeserializeOwned,
+    {
+        Deserializer::from_str_with_options(s, self)?.deserialize()
+    }
+
+    /// Deserializes the config from a string slice using these options,
+    /// also returning every key which the type being deserialized into does not use.
+    ///
+    /// Unknown keys are returned in the order they appear in the document.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use corn::DeserializerOptions;
+    /// use serde::Deserialize;
+    ///
+    /// #[derive(Deserialize)]
+    /// struct Server {
+    ///     port: Option<u16>,
+    /// }
+    ///
+    /// #[derive(Deserialize)]
+    /// struct Config {
+    ///     server: Server,
+    /// }
+    ///
+    /// let (_, unknown) = DeserializerOptions::new()
+    ///     .from_str_with_unknown_keys::<Config>("{ server.prot = 8080 }")
+    ///     .unwrap();
+    ///
+    /// assert_eq!(unknown[0].path, "server.prot");
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Fails under the same conditions as [`crate::from_str`].
+    pub fn from_str_with_unknown_keys<T>(&self, s: &'a str) -> Result<(T, Vec<UnknownKey>)>
+    where
+        T: DeserializeOwned,
+    {
+        let mut deserializer = Deserializer::from_str_with_options(s, self)?;
+        let value = deserializer.deserialize()?;
+
+        Ok((value, deserializer.unknown_keys()))
+    }
+
+    /// Deserializes the config from a byte slice using these options.
+    ///
+    /// # Errors
+    ///
+    /// Fails under the same conditions as [`crate::from_slice`].
+    pub fn from_slice<T>(&self, bytes: &'a [u8]) -> Result<T>
+    where
+        T: DeserializeOwned,
+    {
+        match std::str::from_utf8(bytes) {
+            Ok(s) => self.from_str(s),
+            Err(e) => Err(Error::DeserializationError {
+                message: e.to_string(),
+                path: None,
+                context: None,
+            }),
+        }
+    }
+}
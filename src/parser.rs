@@ -1,14 +1,19 @@
 use indexmap::IndexMap;
 use std::borrow::Cow;
-use std::collections::HashMap;
-use std::env::var;
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
 use std::fmt::Formatter;
+use std::fs;
+use std::path::{Path, PathBuf};
 
 use pest::iterators::Pair;
 use pest::Parser;
 
-use crate::error::{Error, Result};
-use crate::{Inputs, Object, Value};
+use crate::diagnostic::{Diagnostic, Severity};
+use crate::error::{Context, Error, Result};
+use crate::options::{ImportAccess, InputPrecedence, ParseOptions};
+use crate::span::{LineIndex, Span, Spanned, SpannedEntry, SpannedObject, SpannedValue};
+use crate::Value;
 
 #[derive(pest_derive::Parser)]
 #[grammar = "grammar.pest"]
@@ -20,45 +25,358 @@ impl std::fmt::Display for Rule {
     }
 }
 
-struct CornParser<'a> {
+struct CornParser<'a, 'o> {
     input_block: Option<Pair<'a, Rule>>,
-    inputs: Inputs<'a>,
+    inputs: HashMap<&'a str, Spanned<SpannedValue<'a>>>,
+    line_index: LineIndex<'a>,
+    /// Canonical paths of the files being parsed,
+    /// from the file passed to `parse_file` down to the current file.
+    /// Empty when parsing a string with no associated file.
+    import_chain: Vec<PathBuf>,
+    options: &'o ParseOptions<'a>,
+    /// Errors recorded while collecting diagnostics.
+    /// When `None`, evaluation stops at the first error instead.
+    diagnostics: Option<RefCell<Vec<Diagnostic>>>,
+    /// Problems which do not stop the document being parsed.
+    warnings: RefCell<Vec<Diagnostic>>,
+    /// The names of inputs defined in the `let` block, in the order they are defined.
+    input_names: Vec<Spanned<&'a str>>,
+    /// Inputs which have been evaluated,
+    /// so that each shadowed input is only reported once.
+    used_inputs: RefCell<HashSet<&'a str>>,
 }
 
-impl<'a> CornParser<'a> {
-    pub fn new(input_block: Option<Pair<'a, Rule>>) -> Self {
+impl<'a, 'o> CornParser<'a, 'o> {
+    pub fn new(
+        input_block: Option<Pair<'a, Rule>>,
+        line_index: LineIndex<'a>,
+        import_chain: Vec<PathBuf>,
+        options: &'o ParseOptions<'a>,
+    ) -> Self {
         let inputs = HashMap::new();
         Self {
             input_block,
             inputs,
+            line_index,
+            import_chain,
+            options,
+            diagnostics: None,
+            warnings: RefCell::default(),
+            input_names: vec![],
+            used_inputs: RefCell::default(),
         }
     }
 
-    pub fn parse(mut self, object_block: Pair<'a, Rule>) -> Result<Value<'a>> {
+    /// Evaluates the document, returning its value along with any warnings.
+    pub fn parse(
+        mut self,
+        object_block: Pair<'a, Rule>,
+    ) -> Result<(Spanned<SpannedValue<'a>>, Vec<Diagnostic>)> {
+        let value = self.evaluate(object_block)?;
+
+        let mut warnings = self.warnings.into_inner();
+        warnings.sort_by_key(|warning| warning.span.start.offset);
+
+        Ok((value, warnings))
+    }
+
+    /// Evaluates the document in the same way as `parse`,
+    /// but records each error as a diagnostic and carries on
+    /// with the next pair, array element or assignment.
+    pub fn diagnose(mut self, object_block: Pair<'a, Rule>) -> Vec<Diagnostic> {
+        self.diagnostics = Some(RefCell::default());
+
+        let span = self.span(&object_block);
+        let result = self.evaluate(object_block);
+
+        let mut diagnostics = self.diagnostics.take().unwrap_or_default().into_inner();
+        diagnostics.append(self.warnings.get_mut());
+        if let Err(err) = result {
+            diagnostics.push(
+                err.diagnostic()
+                    .unwrap_or_else(|| Diagnostic::error(err.to_string(), span)),
+            );
+        }
+
+        diagnostics
+    }
+
+    fn evaluate(&mut self, object_block: Pair<'a, Rule>) -> Result<Spanned<SpannedValue<'a>>> {
+        // inputs are collected from the syntax tree rather than during evaluation,
+        // so those only referenced from branches which are never evaluated,
+        // such as the right of a `??`, still count as used.
+        // inputs referenced by other inputs only count if those are used themselves
+        let mut input_references = HashMap::<_, Vec<_>>::new();
+        for assignment in self
+            .input_block
+            .iter()
+            .flat_map(|block| block.clone().into_inner())
+        {
+            let mut pairs = assignment.into_inner().flatten();
+            let name = pairs
+                .next()
+                .expect("input assignments should have a name")
+                .as_str();
+
+            input_references
+                .entry(name)
+                .or_default()
+                .extend(referenced_inputs(pairs));
+        }
+
+        let mut used = HashSet::new();
+        let mut pending =
+            referenced_inputs(object_block.clone().into_inner().flatten()).collect::<Vec<_>>();
+
+        while let Some(name) = pending.pop() {
+            if used.insert(name) {
+                pending.extend(input_references.get(name).into_iter().flatten());
+            }
+        }
+
         if let Some(input_block) = self.input_block.take() {
             self.parse_assign_block(input_block)?;
         }
 
+        let span = self.span(&object_block);
         let value_block = self.parse_object(object_block)?;
-        Ok(Value::Object(value_block))
-    }
-
-    /// Parses a pair of tokens (marked as a `Rule`) into a `Value`.
-    fn parse_value(&self, pair: Pair<'a, Rule>) -> Result<Value<'a>> {
-        match pair.as_rule() {
-            Rule::object => Ok(Value::Object(self.parse_object(pair)?)),
-            Rule::array => Ok(Value::Array(self.parse_array(pair)?)),
-            Rule::string => Ok(Value::String(self.parse_string(pair)?)),
-            Rule::integer => Ok(Value::Integer(Self::parse_integer(pair))),
-            Rule::float => Ok(Value::Float(Self::parse_float(&pair))),
-            Rule::boolean => Ok(Value::Boolean(Self::parse_bool(&pair))),
-            Rule::null => Ok(Value::Null(None)),
+
+        let mut reported = HashSet::new();
+        for name in &self.input_names {
+            if !used.contains(name.value) && reported.insert(name.value) {
+                self.warn(
+                    format!("input `{}` is never used", name.value),
+                    Context::new(name.span)
+                        .label("defined here")
+                        .help("remove it from the `let` block"),
+                );
+            }
+        }
+
+        Ok(Spanned::new(span, SpannedValue::Object(value_block)))
+    }
+
+    /// Records a problem which does not stop the document being parsed.
+    fn warn(&self, message: String, context: Context) {
+        self.warnings.borrow_mut().push(Diagnostic::from_context(
+            Severity::Warning,
+            message,
+            &context,
+        ));
+    }
+
+    /// Handles the result of evaluating the part of the document at `span`.
+    ///
+    /// When collecting diagnostics, an error is recorded and `None` returned
+    /// so that evaluation can continue. Otherwise, the error is passed on.
+    fn recover<T>(&self, result: Result<T>, span: Span) -> Result<Option<T>> {
+        match (result, &self.diagnostics) {
+            (Ok(value), _) => Ok(Some(value)),
+            (Err(err), Some(diagnostics)) => {
+                diagnostics.borrow_mut().push(
+                    err.diagnostic()
+                        .unwrap_or_else(|| Diagnostic::error(err.to_string(), span)),
+                );
+                Ok(None)
+            }
+            (Err(err), None) => Err(err),
+        }
+    }
+
+    fn span(&self, pair: &Pair<'a, Rule>) -> Span {
+        self.line_index.span(&pair.as_span())
+    }
+
+    /// Parses a pair of tokens (marked as a `Rule`) into a `SpannedValue`.
+    fn parse_value(&self, pair: Pair<'a, Rule>) -> Result<Spanned<SpannedValue<'a>>> {
+        let span = self.span(&pair);
+
+        let value = match pair.as_rule() {
+            Rule::object => SpannedValue::Object(self.parse_object(pair)?),
+            Rule::array => SpannedValue::Array(self.parse_array(pair)?),
+            Rule::string => SpannedValue::String(self.parse_string(pair)?),
+            Rule::integer => SpannedValue::Integer(self.parse_integer(pair)?),
+            Rule::float => SpannedValue::Float(self.parse_float(&pair)?),
+            Rule::boolean => SpannedValue::Boolean(Self::parse_bool(&pair)),
+            Rule::null => SpannedValue::Null,
             Rule::input => {
                 let key = pair.as_str();
-                self.get_input(key)
+                return self.get_input(key, span);
+            }
+            Rule::cast => return self.parse_cast(pair),
+            Rule::required => return self.parse_required(pair),
+            Rule::fallback => return self.parse_fallback(pair),
+            _ => unreachable!(),
+        };
+
+        Ok(Spanned::new(span, value))
+    }
+
+    /// Resolves the input in a `Rule::cast` and converts it to the requested type.
+    ///
+    /// String values have their text read as a Corn value,
+    /// allowing inputs such as environment variables to hold numbers, booleans and objects.
+    /// Integers can be converted to floats, but otherwise the value must already be of the requested type.
+    fn parse_cast(&self, pair: Pair<'a, Rule>) -> Result<Spanned<SpannedValue<'a>>> {
+        let operand = pair
+            .clone()
+            .into_inner()
+            .next()
+            .expect("casts should contain an input");
+
+        let name = input_name(&operand);
+        let span = self.span(&operand);
+
+        self.try_parse_cast(pair)?
+            .ok_or_else(|| self.unresolved_input(name, span))
+    }
+
+    /// Parses a `Rule::cast` in the same way as `parse_cast`,
+    /// but returns `None` if the input is not defined.
+    fn try_parse_cast(&self, pair: Pair<'a, Rule>) -> Result<Option<Spanned<SpannedValue<'a>>>> {
+        assert_eq!(pair.as_rule(), Rule::cast);
+
+        let span = self.span(&pair);
+        let mut rules = pair.into_inner();
+
+        let operand = rules.next().expect("casts should contain an input");
+        let name = input_name(&operand);
+        let cast_type = rules.next().expect("casts should contain a type").as_str();
+
+        let cast_error = |reason: String| {
+            Error::InvalidCastError(
+                format!("`{name}` to {cast_type}: {reason}"),
+                Context::new(span)
+                    .label(format!("cannot be converted to {cast_type}"))
+                    .into(),
+            )
+        };
+
+        let value = match operand.as_rule() {
+            Rule::required => self.parse_required(operand)?,
+            _ => match self.try_get_input(name, span)? {
+                Some(value) => value,
+                None => return Ok(None),
+            },
+        };
+
+        let converted = match value.value {
+            SpannedValue::String(text) => self
+                .parse_standalone_value(&text)
+                .and_then(|value| {
+                    convert_value(SpannedValue::from_value(value, span).value, cast_type)
+                })
+                .ok_or_else(|| {
+                    let expected = if cast_type == "value" {
+                        "Corn value"
+                    } else {
+                        cast_type
+                    };
+                    cast_error(format!("`{text}` is not a valid {expected}"))
+                })?,
+            value => {
+                let found = value.clone().into_value().to_string();
+                convert_value(value, cast_type)
+                    .ok_or_else(|| cast_error(format!("expected {cast_type} but found {found}")))?
             }
+        };
+
+        Ok(Some(Spanned::new(span, converted)))
+    }
+
+    /// Resolves the input in a `Rule::required`
+    /// from a registered `InputResolver` or the host inputs.
+    /// Inputs defined in the `let` block are ignored.
+    ///
+    /// If the input is not set, fails with the message given alongside it.
+    fn parse_required(&self, pair: Pair<'a, Rule>) -> Result<Spanned<SpannedValue<'a>>> {
+        assert_eq!(pair.as_rule(), Rule::required);
+
+        let span = self.span(&pair);
+        let mut rules = pair.into_inner();
+
+        let name = rules
+            .next()
+            .expect("required inputs should contain an input")
+            .as_str();
+
+        if let Some(value) = self.resolve_input(name, span)? {
+            return Ok(value);
+        }
+
+        if let Some(value) = self.host_input(name, span) {
+            return Ok(value);
+        }
+
+        let message = self.parse_string(
+            rules
+                .next()
+                .expect("required inputs should contain a message"),
+        )?;
+
+        Err(Error::RequiredInputError {
+            input: name.to_string(),
+            message: message.into_owned(),
+            context: Context::new(span).label("required input is not set").into(),
+        })
+    }
+
+    /// Evaluates the input or cast on the left of a `Rule::fallback`,
+    /// or the value on the right if that input is not defined.
+    ///
+    /// Inputs which the `EnvAccess` option does not allow reading
+    /// are treated as not defined, so sandboxed files still get their fallback.
+    fn parse_fallback(&self, pair: Pair<'a, Rule>) -> Result<Spanned<SpannedValue<'a>>> {
+        assert_eq!(pair.as_rule(), Rule::fallback);
+
+        let mut rules = pair.into_inner();
+
+        let primary = rules
+            .next()
+            .expect("fallbacks should contain a primary value");
+        let fallback = rules
+            .next()
+            .expect("fallbacks should contain a fallback value");
+
+        let value = match primary.as_rule() {
+            Rule::input => self.try_get_input(primary.as_str(), self.span(&primary)),
+            Rule::cast => self.try_parse_cast(primary),
             _ => unreachable!(),
+        };
+
+        let value = match value {
+            Err(Error::EnvAccessDeniedError(..)) => None,
+            value => value?,
+        };
+
+        match value {
+            Some(value) => Ok(value),
+            None => self.parse_value(fallback),
+        }
+    }
+
+    /// Reads `text` as a single Corn value.
+    /// Returns `None` if it is not valid, or if it references any inputs.
+    fn parse_standalone_value(&self, text: &str) -> Option<Value<'static>> {
+        let pair = AstParser::parse(Rule::standalone_value, text)
+            .ok()?
+            .next()?;
+
+        let has_inputs = pair
+            .clone()
+            .into_inner()
+            .flatten()
+            .any(|pair| pair.as_rule() == Rule::input);
+
+        if has_inputs {
+            return None;
         }
+
+        let parser = CornParser::new(None, LineIndex::new(text), vec![], self.options);
+        parser
+            .parse_value(pair)
+            .ok()
+            .map(|value| Value::from(value).into_owned())
     }
 
     fn parse_bool(pair: &Pair<'_, Rule>) -> bool {
@@ -70,30 +388,60 @@ impl<'a> CornParser<'a> {
         }
     }
 
-    fn parse_integer(pair: Pair<'_, Rule>) -> i64 {
+    /// Parses a `Rule::integer` into an `i128`,
+    /// which can hold the full range of both `i64` and `u64`.
+    /// Literals which do not fit are reported at their location rather than wrapped.
+    fn parse_integer(&self, pair: Pair<'a, Rule>) -> Result<i128> {
         assert_eq!(pair.as_rule(), Rule::integer);
+        let span = self.span(&pair);
         let sub_pair = pair
             .into_inner()
             .next()
             .expect("integers should contain a sub-rule of their type");
 
-        match sub_pair.as_rule() {
-            Rule::decimal_integer => sub_pair
-                .as_str()
-                .replace('_', "")
-                .parse()
-                .expect("decimal integer rules should match valid rust integers"),
-            Rule::hex_integer => i64::from_str_radix(&sub_pair.as_str()[2..], 16)
-                .expect("hex integer rules contain valid hex values"),
+        let value = match sub_pair.as_rule() {
+            Rule::decimal_integer => sub_pair.as_str().replace('_', "").parse().ok(),
+            Rule::hex_integer => i128::from_str_radix(&sub_pair.as_str()[2..], 16).ok(),
             _ => unreachable!(),
-        }
+        };
+
+        value.ok_or_else(|| {
+            Error::IntegerOverflowError(
+                sub_pair.as_str().to_string(),
+                Context::new(span)
+                    .label("does not fit in a 128-bit signed integer")
+                    .help(format!(
+                        "integers must be between {} and {}",
+                        i128::MIN,
+                        i128::MAX
+                    ))
+                    .into(),
+            )
+        })
     }
 
-    fn parse_float(pair: &Pair<'_, Rule>) -> f64 {
+    /// Parses a `Rule::float` into an `f64`.
+    /// Literals too large to be represented are reported instead of becoming infinite.
+    fn parse_float(&self, pair: &Pair<'a, Rule>) -> Result<f64> {
         assert_eq!(pair.as_rule(), Rule::float);
+
         pair.as_str()
-            .parse()
-            .expect("float rules should match valid rust floats")
+            .parse::<f64>()
+            .ok()
+            .filter(|value| value.is_finite())
+            .ok_or_else(|| {
+                Error::FloatOverflowError(
+                    pair.as_str().to_string(),
+                    Context::new(self.span(pair))
+                        .label("too large for a 64-bit float")
+                        .help(format!(
+                            "floats must be between {:e} and {:e}",
+                            f64::MIN,
+                            f64::MAX
+                        ))
+                        .into(),
+                )
+            })
     }
 
     /// Collects each `char` in a `Rule::string`
@@ -114,10 +462,19 @@ impl<'a> CornParser<'a> {
                 Rule::char => full_string.push(Self::parse_char(&pair)),
                 Rule::input => {
                     let input_name = pair.as_str();
-                    let value = self.get_input(input_name)?;
-                    match value {
-                        Value::String(value) => full_string.push_str(&value),
-                        _ => return Err(Error::InvalidInterpolationError(input_name.to_string())),
+                    let span = self.span(&pair);
+                    let value = self.get_input(input_name, span)?;
+                    match value.value {
+                        SpannedValue::String(value) => full_string.push_str(&value),
+                        value => {
+                            return Err(Error::InvalidInterpolationError(
+                                input_name.to_string(),
+                                Context::new(span)
+                                    .label(format!("this is {}, not a string", a_type(&value)))
+                                    .help("only inputs containing strings can be interpolated")
+                                    .into(),
+                            ))
+                        }
                     }
                 }
                 _ => unreachable!(),
@@ -161,13 +518,15 @@ impl<'a> CornParser<'a> {
     }
 
     /// Parses each rule in a `Rule::array`
-    /// to form a vector of `Value`s.
-    fn parse_array(&self, block: Pair<'a, Rule>) -> Result<Vec<Value<'a>>> {
+    /// to form a vector of `SpannedValue`s.
+    fn parse_array(&self, block: Pair<'a, Rule>) -> Result<Vec<Spanned<SpannedValue<'a>>>> {
         assert_eq!(block.as_rule(), Rule::array);
 
         let mut arr = vec![];
 
         for pair in block.into_inner() {
+            let span = self.span(&pair);
+
             match pair.as_rule() {
                 Rule::spread => {
                     let input = pair
@@ -176,14 +535,32 @@ impl<'a> CornParser<'a> {
                         .expect("spread operators should contain an input");
 
                     let input_name = input.as_str();
-                    let value = self.parse_value(input)?;
+                    let input_span = self.span(&input);
+                    let result = self.parse_value(input).and_then(|value| match value.value {
+                        SpannedValue::Array(other) => {
+                            if other.is_empty() {
+                                self.warn_empty_spread(input_name, "array", span);
+                            }
 
-                    match value {
-                        Value::Array(other) => arr.extend(other),
-                        _ => return Err(Error::InvalidSpreadError(input_name.to_string())),
+                            arr.extend(other);
+                            Ok(())
+                        }
+                        value => Err(Error::InvalidSpreadError(
+                            input_name.to_string(),
+                            Context::new(input_span)
+                                .label(format!("this is {}, not an array", a_type(&value)))
+                                .help("only arrays can be spread into arrays")
+                                .into(),
+                        )),
+                    });
+
+                    self.recover(result, span)?;
+                }
+                _ => {
+                    if let Some(value) = self.recover(self.parse_value(pair), span)? {
+                        arr.push(value);
                     }
                 }
-                _ => arr.push(self.parse_value(pair)?),
             };
         }
 
@@ -191,47 +568,101 @@ impl<'a> CornParser<'a> {
     }
 
     /// Parses each key/value pair in a `Rule::object`
-    /// to form a `IndexMap` of Values.
+    /// to form a `IndexMap` of `SpannedValue`s.
     ///
     /// An `IndexMap` is used to ensure keys
     /// always output in the same order.
-    fn parse_object(&self, block: Pair<'a, Rule>) -> Result<Object<'a>> {
+    fn parse_object(&self, block: Pair<'a, Rule>) -> Result<SpannedObject<'a>> {
         assert_eq!(block.as_rule(), Rule::object);
 
         let mut obj = IndexMap::new();
 
+        // paths whose values came from a spread,
+        // which can each be overridden once without being reported as duplicates
+        let mut spread_paths = HashSet::new();
+
         for pair in block.into_inner() {
             match pair.as_rule() {
                 Rule::pair => {
+                    let pair_span = self.span(&pair);
                     let mut path_rules = pair.into_inner();
 
                     let path = path_rules
                         .next()
                         .expect("object pairs should contain a key");
 
-                    let paths = Self::parse_path(path);
+                    let path_span = self.span(&path);
+                    let paths = self.parse_path(path);
 
                     let value = self.parse_value(
                         path_rules
                             .next()
                             .expect("object pairs should contain a value"),
-                    )?;
+                    );
+
+                    if let Some(value) = self.recover(value, pair_span)? {
+                        let key = paths
+                            .iter()
+                            .map(|part| part.value.clone())
+                            .collect::<Vec<_>>();
+                        let replaces_with_object = matches!(value.value, SpannedValue::Object(_));
+
+                        let result = Self::add_at_path(&mut obj, &paths, value, pair_span)
+                            .and_then(|replaced| match replaced {
+                                Some(existing) if !spread_paths.contains(&key) => self
+                                    .duplicate_key(
+                                        &paths,
+                                        path_span,
+                                        &existing,
+                                        replaces_with_object,
+                                    ),
+                                _ => Ok(()),
+                            });
 
-                    obj = Self::add_at_path(obj, &paths, value)?;
+                        // the value at this path is now set explicitly,
+                        // so is no longer from a spread, and nor are the objects containing it
+                        spread_paths.retain(|spread: &Vec<_>| {
+                            !spread.starts_with(&key) && !key.starts_with(spread)
+                        });
+
+                        self.recover(result, pair_span)?;
+                    }
                 }
                 Rule::spread => {
+                    let span = self.span(&pair);
                     let input = pair
                         .into_inner()
                         .next()
                         .expect("spread operators should contain an input");
 
                     let input_name = input.as_str();
-                    let value = self.parse_value(input)?;
+                    let input_span = self.span(&input);
+                    let result = self.parse_value(input).and_then(|value| match value.value {
+                        SpannedValue::Object(other) => {
+                            if other.is_empty() {
+                                self.warn_empty_spread(input_name, "object", span);
+                            }
 
-                    match value {
-                        Value::Object(other) => obj.extend(other),
-                        _ => return Err(Error::InvalidSpreadError(input_name.to_string())),
-                    }
+                            for (key, entry) in &other {
+                                collect_paths(
+                                    vec![key.clone()],
+                                    &entry.value.value,
+                                    &mut spread_paths,
+                                );
+                            }
+                            obj.extend(other);
+                            Ok(())
+                        }
+                        value => Err(Error::InvalidSpreadError(
+                            input_name.to_string(),
+                            Context::new(input_span)
+                                .label(format!("this is {}, not an object", a_type(&value)))
+                                .help("only objects can be spread into objects")
+                                .into(),
+                        )),
+                    });
+
+                    self.recover(result, span)?;
                 }
                 _ => unreachable!(),
             }
@@ -240,57 +671,134 @@ impl<'a> CornParser<'a> {
         Ok(obj)
     }
 
-    fn parse_path(path: Pair<Rule>) -> Vec<Cow<str>> {
+    /// Warns that the spread at `span` has no effect, as `input_name` is empty.
+    fn warn_empty_spread(&self, input_name: &str, kind: &str, span: Span) {
+        self.warn(
+            format!("spread of empty {kind} `{input_name}` has no effect"),
+            Context::new(span)
+                .label(format!("this {kind} is empty"))
+                .help("remove the spread"),
+        );
+    }
+
+    fn parse_path(&self, path: Pair<'a, Rule>) -> Vec<Spanned<Cow<'a, str>>> {
         path.into_inner()
-            .map(|pair| match pair.as_rule() {
-                Rule::regular_path_seg => Cow::Borrowed(pair.as_str()),
-                Rule::quoted_path_seg => Cow::Owned(
-                    pair.into_inner()
-                        .next()
-                        .expect("quoted paths should contain an inner value")
-                        .as_str()
-                        .replace('\\', ""),
-                ),
-                _ => unreachable!(),
-            })
+            .map(|pair| Spanned::new(self.span(&pair), parse_path_seg(pair)))
             .collect::<Vec<_>>()
     }
 
-    /// Adds `Value` at the `path` in `obj`.
+    /// Adds `value` at the `path` in `obj`.
     ///
     /// `path` is an array where each entry represents another object key,
     /// for example `foo.bar` is represented as `["foo", "bar"]`.
     ///
     /// Objects are created up to the required depth recursively.
+    /// Any objects created this way are attributed to `pair_span`.
+    ///
+    /// Returns the entry previously at `path`, if it has been replaced.
     fn add_at_path(
-        mut obj: Object<'a>,
-        path: &[Cow<'a, str>],
-        value: Value<'a>,
-    ) -> Result<Object<'a>> {
+        obj: &mut SpannedObject<'a>,
+        path: &[Spanned<Cow<'a, str>>],
+        value: Spanned<SpannedValue<'a>>,
+        pair_span: Span,
+    ) -> Result<Option<SpannedEntry<'a>>> {
         let (part, path_rest) = path
             .split_first()
             .expect("paths should contain at least 1 segment");
 
         if path_rest.is_empty() {
-            obj.insert(part.clone(), value);
-            return Ok(obj);
+            return Ok(obj.insert(
+                part.value.clone(),
+                SpannedEntry {
+                    key: part.span,
+                    value,
+                },
+            ));
         }
 
-        let child_obj = obj
-            .shift_remove(part)
-            .unwrap_or_else(|| Value::Object(IndexMap::new()));
+        let mut child_entry = obj
+            .shift_remove(&part.value)
+            .unwrap_or_else(|| SpannedEntry {
+                key: part.span,
+                value: Spanned::new(pair_span, SpannedValue::Object(IndexMap::new())),
+            });
 
-        match child_obj {
-            Value::Object(map) => {
-                obj.insert(
-                    part.clone(),
-                    Value::Object(Self::add_at_path(map, path_rest, value)?),
-                );
+        let result = match &mut child_entry.value.value {
+            SpannedValue::Object(map) => Self::add_at_path(map, path_rest, value, pair_span),
+            existing => Err(Error::InvalidPathError(
+                path.iter()
+                    .map(|part| part.value.as_ref())
+                    .collect::<Vec<_>>()
+                    .join("."),
+                Context::new(part.span)
+                    .label(format!("cannot set a key inside {}", a_type(existing)))
+                    .secondary(
+                        child_entry.value.span,
+                        format!(
+                            "`{}` was first set to {} here",
+                            part.value,
+                            a_type(existing)
+                        ),
+                    )
+                    .help(format!(
+                        "use a different key, or set `{}` to an object",
+                        part.value
+                    ))
+                    .into(),
+            )),
+        };
 
-                Ok(obj)
-            }
-            _ => Err(Error::InvalidPathError(path.join("."))),
+        obj.insert(part.value.clone(), child_entry);
+        result
+    }
+
+    /// Reports that the key at `path` was set again, replacing `existing`.
+    ///
+    /// In strict mode this fails with a `DuplicateKeyError`,
+    /// otherwise it is recorded as a warning.
+    fn duplicate_key(
+        &self,
+        path: &[Spanned<Cow<'a, str>>],
+        span: Span,
+        existing: &SpannedEntry<'a>,
+        replaces_with_object: bool,
+    ) -> Result<()> {
+        let key = path
+            .iter()
+            .map(|part| part.value.as_ref())
+            .collect::<Vec<_>>()
+            .join(".");
+
+        let context = match &existing.value.value {
+            SpannedValue::Object(_) if !replaces_with_object => Context::new(span)
+                .label(format!("replaces the object at `{key}`"))
+                .secondary(
+                    existing.key,
+                    format!("`{key}` was first set to an object here"),
+                )
+                .help(format!(
+                    "set the keys inside `{key}` instead, or remove one of the assignments"
+                )),
+            _ => Context::new(span)
+                .label("set again here")
+                .secondary(existing.key, "first set here")
+                .help("remove one of the assignments, or use a different key"),
+        };
+
+        let err = Error::DuplicateKeyError(key, context.into());
+
+        if self.options.strict {
+            return Err(err);
         }
+
+        if let Some(diagnostic) = err.diagnostic() {
+            self.warnings.borrow_mut().push(Diagnostic {
+                severity: Severity::Warning,
+                ..diagnostic
+            });
+        }
+
+        Ok(())
     }
 
     /// Parses the `let { } in` block at the start of files.
@@ -299,41 +807,392 @@ impl<'a> CornParser<'a> {
         assert_eq!(block.as_rule(), Rule::assign_block);
 
         for pair in block.into_inner() {
+            let span = self.span(&pair);
             let mut assign_rules = pair.into_inner();
-            let name = assign_rules
+            let name_rule = assign_rules
                 .next()
-                .expect("input assignments should have a name")
-                .as_str();
+                .expect("input assignments should have a name");
 
-            let value = self.parse_value(
-                assign_rules
-                    .next()
-                    .expect("input assignments should have a value"),
-            )?;
+            let name = name_rule.as_str();
+            let name_span = self.span(&name_rule);
+
+            if let Some(first) = self.input_names.iter().find(|input| input.value == name) {
+                self.warn(
+                    format!("input `{name}` is defined more than once"),
+                    Context::new(name_span)
+                        .label("defined again here")
+                        .secondary(first.span, "first defined here")
+                        .help("remove one of the definitions"),
+                );
+            }
+
+            self.input_names.push(Spanned::new(name_span, name));
 
-            self.inputs.insert(name, value);
+            let value_rule = assign_rules
+                .next()
+                .expect("input assignments should have a value");
+
+            let value = match value_rule.as_rule() {
+                Rule::import => self.parse_import(value_rule),
+                _ => self.parse_value(value_rule),
+            };
+
+            if let Some(value) = self.recover(value, span)? {
+                self.inputs.insert(name, value);
+            }
         }
 
         Ok(())
     }
 
-    /// Attempts to get an input value from the `inputs` map.
-    /// If the `key` starts with `$env_` the system environment variables will be consulted first.
-    fn get_input(&self, key: &'a str) -> Result<Value<'a>> {
-        if let Some(env_name) = key.strip_prefix("$env_") {
-            let var = var(env_name);
+    /// Parses and evaluates the file referenced by a `Rule::import`.
+    ///
+    /// Relative paths are resolved against the directory of the importing file.
+    /// Imports are only allowed when parsing a file,
+    /// and only for files permitted by the `ImportAccess` option.
+    /// The imported value is attributed to the span of the import expression.
+    fn parse_import(&self, pair: Pair<'a, Rule>) -> Result<Spanned<SpannedValue<'a>>> {
+        assert_eq!(pair.as_rule(), Rule::import);
+
+        let span = self.span(&pair);
+        let path = self.parse_string(
+            pair.into_inner()
+                .next()
+                .expect("imports should contain a path"),
+        )?;
+        let path = Path::new(path.as_ref());
+
+        // strings have no location on disk, so cannot import anything,
+        // otherwise untrusted input could read any file by its absolute path
+        let Some(current) = self.import_chain.last() else {
+            return Err(Error::ImportError(
+                format!(
+                    "cannot import `{}` without a base file, use `parse_file` instead",
+                    path.display()
+                ),
+                Context::new(span)
+                    .label("import outside of a file")
+                    .help("parse the file from its path, so imports can be resolved relative to it")
+                    .into(),
+            ));
+        };
+
+        if self.options.imports == ImportAccess::None {
+            return Err(Error::ImportError(
+                format!("cannot import `{}`, imports are disabled", path.display()),
+                Context::new(span)
+                    .label("importing files is not allowed")
+                    .into(),
+            ));
+        }
+
+        let path = current
+            .parent()
+            .expect("files should be inside a directory")
+            .join(path);
+
+        let canonical_path = path.canonicalize().map_err(|err| {
+            import_error(&path, &self.import_chain, &Error::Io(err))
+                .with_context(Context::new(span).label("file could not be read"))
+        })?;
+
+        if !self.options.imports.allows(&canonical_path) {
+            return Err(Error::ImportError(
+                format!(
+                    "cannot import `{}`, it is outside the allowed directory",
+                    path.display()
+                ),
+                Context::new(span)
+                    .label("importing this file is not allowed")
+                    .into(),
+            ));
+        }
+
+        if let Some(start) = self
+            .import_chain
+            .iter()
+            .position(|path| path == &canonical_path)
+        {
+            let cycle = self.import_chain[start..]
+                .iter()
+                .chain([&canonical_path])
+                .map(|path| format!("`{}`", path.display()))
+                .collect::<Vec<_>>()
+                .join(" -> ");
+
+            return Err(Error::ImportCycleError(
+                cycle,
+                Context::new(span)
+                    .label("this file is already being imported")
+                    .into(),
+            ));
+        }
+
+        // errors from other files are reported at the import,
+        // as their own locations do not point into this file
+        // warnings are only reported for the file being parsed
+        let (value, _) =
+            parse_file_in_chain(&canonical_path, self.import_chain.clone(), self.options).map_err(
+                |err| {
+                    match err {
+                        Error::ImportError(..) | Error::ImportCycleError(..) => err,
+                        err => import_error(&canonical_path, &self.import_chain, &err),
+                    }
+                    .with_context(Context::new(span).label("imported here"))
+                },
+            )?;
+        Ok(SpannedValue::from_value(value, span))
+    }
+
+    /// Attempts to get an input value.
+    /// If the `key` starts with a prefix registered to an `InputResolver`,
+    /// such as `$env_`, that resolver is consulted first.
+    ///
+    /// Otherwise, inputs supplied by the host and inputs defined in the `let` block
+    /// are checked in the order set by the `InputPrecedence` option.
+    ///
+    /// The returned value is attributed to `span`, the location of the reference.
+    fn get_input(&self, key: &'a str, span: Span) -> Result<Spanned<SpannedValue<'a>>> {
+        self.try_get_input(key, span)?
+            .ok_or_else(|| self.unresolved_input(key, span))
+    }
+
+    /// Creates an `InputResolveError` for the input `key` referenced at `span`.
+    fn unresolved_input(&self, key: &str, span: Span) -> Error {
+        let help = match key.strip_prefix("$env_") {
+            Some(variable) => format!(
+                "set the `{variable}` environment variable, or define `{key}` in the `let` block"
+            ),
+            None => format!("define `{key}` in the `let` block"),
+        };
+
+        Error::InputResolveError(
+            key.to_string(),
+            Context::new(span).label("not defined").help(help).into(),
+        )
+    }
+
+    /// Attempts to get an input value in the same way as `get_input`,
+    /// but returns `None` if the input is not defined anywhere.
+    fn try_get_input(&self, key: &'a str, span: Span) -> Result<Option<Spanned<SpannedValue<'a>>>> {
+        let first_use = self.used_inputs.borrow_mut().insert(key);
 
-            if let Ok(var) = var {
-                return Ok(Value::String(Cow::Owned(var)));
+        if let Some(value) = self.resolve_input(key, span)? {
+            if first_use && self.inputs.contains_key(key) {
+                self.warn_shadowed(key, span);
             }
+
+            return Ok(Some(value));
         }
 
-        if let Some(value) = self.inputs.get(key) {
-            Ok(value.clone())
-        } else {
-            Err(Error::InputResolveError(key.to_string()))
+        let host_input = || self.host_input(key, span);
+
+        let let_input = || {
+            self.inputs
+                .get(key)
+                .map(|value| Spanned::new(span, value.value.clone()))
+        };
+
+        Ok(match self.options.precedence {
+            InputPrecedence::Host => host_input().or_else(let_input),
+            InputPrecedence::Let => let_input().or_else(host_input),
+        })
+    }
+
+    /// Warns that the input `key` referenced at `span` was resolved by an `InputResolver`,
+    /// so its definition in the `let` block is ignored.
+    fn warn_shadowed(&self, key: &str, span: Span) {
+        let (message, label) = match key.strip_prefix("$env_") {
+            Some(variable) => (
+                format!(
+                    "environment variable `{variable}` overrides input `{key}` in the `let` block"
+                ),
+                "resolved from the environment",
+            ),
+            None => (
+                format!("input `{key}` is resolved by the host, overriding the `let` block"),
+                "resolved by the host",
+            ),
+        };
+
+        let mut context = Context::new(span)
+            .label(label)
+            .help(format!("remove `{key}` from the `let` block"));
+
+        if let Some(name) = self.input_names.iter().find(|name| name.value == key) {
+            context = context.secondary(name.span, "this value is ignored");
+        }
+
+        self.warn(message, context);
+    }
+
+    /// Gets an input value from the `InputResolver` registered for its prefix, if any.
+    fn resolve_input(&self, key: &str, span: Span) -> Result<Option<Spanned<SpannedValue<'a>>>> {
+        match self.options.resolver_for(key) {
+            Some((resolver, name)) => Ok(resolver
+                .resolve(name)
+                .map_err(|err| {
+                    let label = match err {
+                        Error::EnvAccessDeniedError(..) => "reading this variable is not allowed",
+                        _ => "failed to resolve",
+                    };
+
+                    err.with_context(Context::new(span).label(label))
+                })?
+                .map(|value| SpannedValue::from_value(value, span))),
+            None => Ok(None),
+        }
+    }
+
+    /// Gets an input value supplied by the host, if any.
+    fn host_input(&self, key: &str, span: Span) -> Option<Spanned<SpannedValue<'a>>> {
+        self.options
+            .inputs
+            .get(key)
+            .map(|value| SpannedValue::from_value(value.clone(), span))
+    }
+}
+
+/// Describes the type of `value`, such as "an integer", for use in error labels.
+fn a_type(value: &SpannedValue) -> &'static str {
+    match value {
+        SpannedValue::Object(_) => "an object",
+        SpannedValue::Array(_) => "an array",
+        SpannedValue::String(_) => "a string",
+        SpannedValue::Integer(_) => "an integer",
+        SpannedValue::Float(_) => "a float",
+        SpannedValue::Boolean(_) => "a boolean",
+        SpannedValue::Null => "null",
+    }
+}
+
+/// Adds `path`, which holds `value`, to `paths`,
+/// along with the path of every value nested inside it.
+fn collect_paths<'a>(
+    path: Vec<Cow<'a, str>>,
+    value: &SpannedValue<'a>,
+    paths: &mut HashSet<Vec<Cow<'a, str>>>,
+) {
+    if let SpannedValue::Object(obj) = value {
+        for (key, entry) in obj {
+            let mut child = path.clone();
+            child.push(key.clone());
+            collect_paths(child, &entry.value.value, paths);
         }
     }
+
+    paths.insert(path);
+}
+
+/// Gets the names of the inputs referenced by `pairs`,
+/// which should include every nested pair.
+fn referenced_inputs<'a>(
+    pairs: impl Iterator<Item = Pair<'a, Rule>>,
+) -> impl Iterator<Item = &'a str> {
+    pairs
+        .filter(|pair| pair.as_rule() == Rule::input)
+        .map(|pair| pair.as_str())
+}
+
+/// Gets the name of the input referenced by a `Rule::input` or `Rule::required`.
+fn input_name<'a>(pair: &Pair<'a, Rule>) -> &'a str {
+    match pair.as_rule() {
+        Rule::input => pair.as_str(),
+        Rule::required => pair
+            .clone()
+            .into_inner()
+            .next()
+            .expect("required inputs should contain an input")
+            .as_str(),
+        _ => unreachable!(),
+    }
+}
+
+/// Converts `value` to the type named by a `Rule::cast_type`,
+/// or returns `None` if it cannot be converted.
+fn convert_value<'a>(value: SpannedValue<'a>, cast_type: &str) -> Option<SpannedValue<'a>> {
+    match (cast_type, value) {
+        ("float", SpannedValue::Integer(value)) => Some(SpannedValue::Float(value as f64)),
+        ("value", value)
+        | ("integer", value @ SpannedValue::Integer(_))
+        | ("float", value @ SpannedValue::Float(_))
+        | ("boolean", value @ SpannedValue::Boolean(_))
+        | ("null", value @ SpannedValue::Null)
+        | ("array", value @ SpannedValue::Array(_))
+        | ("object", value @ SpannedValue::Object(_)) => Some(value),
+        _ => None,
+    }
+}
+
+/// Gets the key represented by a single segment of a `Rule::path`,
+/// removing the quotes and escapes from quoted segments.
+fn parse_path_seg(pair: Pair<'_, Rule>) -> Cow<'_, str> {
+    match pair.as_rule() {
+        Rule::regular_path_seg => Cow::Borrowed(pair.as_str()),
+        Rule::quoted_path_seg => Cow::Owned(
+            pair.into_inner()
+                .next()
+                .expect("quoted paths should contain an inner value")
+                .as_str()
+                .replace('\\', ""),
+        ),
+        _ => unreachable!(),
+    }
+}
+
+/// Parses a standalone dot-notation path, such as `foo.'bar.baz'`,
+/// into its individual keys.
+pub(crate) fn parse_key_path(path: &str) -> Result<Vec<Cow<'_, str>>> {
+    let mut rules = AstParser::parse(Rule::path, path)
+        .map_err(|err| Error::ParserError(describe_rules(err)))?;
+    let pair = rules.next().expect("should contain a path rule");
+
+    if pair.as_str().len() != path.len() {
+        let position = pest::Position::new(path, pair.as_str().len())
+            .expect("path rule should end inside input");
+
+        return Err(Error::ParserError(Box::new(
+            pest::error::Error::new_from_pos(
+                pest::error::ErrorVariant::CustomError {
+                    message: String::from("unexpected character in path"),
+                },
+                position,
+            ),
+        )));
+    }
+
+    Ok(pair.into_inner().map(parse_path_seg).collect())
+}
+
+/// Reads and parses the file at the canonical `path`,
+/// which is imported by the last file in `import_chain`,
+/// returning its value along with any warnings.
+pub(crate) fn parse_file_in_chain(
+    path: &Path,
+    mut import_chain: Vec<PathBuf>,
+    options: &ParseOptions,
+) -> Result<(Value<'static>, Vec<Diagnostic>)> {
+    let file = fs::read_to_string(path)?;
+    import_chain.push(path.to_path_buf());
+
+    parse_in_chain(&file, import_chain, options)
+        .map(|(value, warnings)| (Value::from(value).into_owned(), warnings))
+}
+
+/// Creates an `ImportError` for a failure while importing `path`,
+/// listing each file in `import_chain` it was imported through.
+fn import_error(path: &Path, import_chain: &[PathBuf], err: &Error) -> Error {
+    let chain = import_chain
+        .iter()
+        .rev()
+        .map(|path| format!("`{}`", path.display()))
+        .collect::<Vec<_>>()
+        .join(" <- ");
+
+    Error::ImportError(
+        format!("`{}` (imported from {chain}): {err}", path.display()),
+        None,
+    )
 }
 
 /// Takes a multiline string and trims the maximum amount of
@@ -392,6 +1251,8 @@ fn trim_multiline_string(string: &str) -> String {
 /// Will fail if the input contains invalid Corn for another reason,
 /// including references to undefined inputs or dot-notation for non-object values.
 /// Will fail if the input cannot be deserialized for any reaon.
+/// Will fail if the input contains an import, as there is no file to resolve it from.
+/// Use [`parse_file`] to allow imports.
 ///
 /// Any of the above will return a specific error type with details.
 ///
@@ -400,26 +1261,177 @@ fn trim_multiline_string(string: &str) -> String {
 /// If the internal AST parser produces a tree in an invalid structure,
 /// the function will panic.
 /// This indicates a severe error in the library and should never occur.
-pub fn parse(file: &str) -> Result<Value> {
+pub fn parse(file: &str) -> Result<Value<'_>> {
+    parse_spanned(file).map(Value::from)
+}
+
+/// Parses the input string in the same way as [`parse`],
+/// but keeps the source location of every key and value.
+///
+/// # Examples
+///
+/// ```rust
+/// use corn::{parse_spanned, SpannedValue};
+///
+/// let corn = "{\n  foo = 42\n}";
+///
+/// let config = parse_spanned(corn).unwrap();
+/// let SpannedValue::Object(obj) = config.value else { unreachable!() };
+///
+/// let foo = &obj["foo"];
+/// assert_eq!((foo.key.start.line, foo.key.start.column), (2, 3));
+/// assert_eq!(&corn[foo.value.span.range()], "42");
+/// ```
+///
+/// # Errors
+///
+/// Fails under the same conditions as [`parse`].
+///
+/// # Panics
+///
+/// If the internal AST parser produces a tree in an invalid structure,
+/// the function will panic.
+/// This indicates a severe error in the library and should never occur.
+pub fn parse_spanned(file: &str) -> Result<Spanned<SpannedValue<'_>>> {
+    parse_in_chain(file, vec![], &ParseOptions::default()).map(|(value, _)| value)
+}
+
+/// Parses the input string in the same way as [`parse`],
+/// also returning any warnings about problems which did not stop it being parsed.
+///
+/// Warnings are given for:
+///
+/// - Inputs in the `let` block which are never used.
+/// - Inputs in the `let` block which are ignored,
+///   as they are resolved from elsewhere, such as an environment variable.
+/// - Spreads of empty objects or arrays.
+/// - Keys which are set more than once. See [`ParseOptions::strict`].
+///
+/// # Examples
+///
+/// ```rust
+/// use corn::parse_with_warnings;
+///
+/// let corn = "let { $port = 8080 $host = \"localhost\" } in { port = $port }";
+///
+/// let (config, warnings) = parse_with_warnings(corn).unwrap();
+///
+/// assert_eq!(serde_json::to_string(&config).unwrap(), "{\"port\":8080}");
+/// assert_eq!(warnings.len(), 1);
+/// assert_eq!(warnings[0].message, "input `$host` is never used");
+/// ```
+///
+/// # Errors
+///
+/// Fails under the same conditions as [`parse`].
+pub fn parse_with_warnings(file: &str) -> Result<(Value<'_>, Vec<Diagnostic>)> {
+    ParseOptions::default().parse_with_warnings(file)
+}
+
+/// Reads and parses the Corn file at `path`.
+///
+/// Unlike [`parse`], this allows the file to import other files
+/// using `$input = import "./other.corn"` inside its `let` block.
+/// Relative import paths are resolved against the directory of the importing file.
+/// Each imported file is evaluated on its own,
+/// so it cannot see the inputs of the file importing it.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use corn::parse_file;
+///
+/// let config = parse_file("config/service.corn").unwrap();
+/// ```
+///
+/// # Errors
+///
+/// Fails under the same conditions as [`parse`].
+/// Will also fail if the file, or any file it imports, cannot be read,
+/// or if a file imports itself either directly or through other files.
+/// Imports can be disabled or restricted to a directory using [`ParseOptions::imports`].
+///
+/// Errors inside imported files are reported along with the chain of imports leading to them.
+///
+/// # Panics
+///
+/// If the internal AST parser produces a tree in an invalid structure,
+/// the function will panic.
+/// This indicates a severe error in the library and should never occur.
+pub fn parse_file(path: impl AsRef<Path>) -> Result<Value<'static>> {
+    ParseOptions::default().parse_file(path)
+}
+
+/// Parses the input string,
+/// returning its value along with any warnings.
+pub(crate) fn parse_in_chain<'a>(
+    file: &'a str,
+    import_chain: Vec<PathBuf>,
+    options: &ParseOptions<'a>,
+) -> Result<(Spanned<SpannedValue<'a>>, Vec<Diagnostic>)> {
+    let (parser, object_block) = build_parser(file, import_chain, options)?;
+    parser.parse(object_block)
+}
+
+/// Evaluates the input string, collecting an error diagnostic
+/// for each part of the document which fails to evaluate.
+///
+/// # Errors
+///
+/// Will fail if the input contains a syntax error.
+pub(crate) fn diagnose_in_chain<'a>(
+    file: &'a str,
+    import_chain: Vec<PathBuf>,
+    options: &ParseOptions<'a>,
+) -> std::result::Result<Vec<Diagnostic>, Box<pest::error::Error<Rule>>> {
+    let (parser, object_block) = build_parser(file, import_chain, options)?;
+    Ok(parser.diagnose(object_block))
+}
+
+/// Parses the syntax of the input string,
+/// returning a parser ready to evaluate its object block.
+fn build_parser<'a, 'o>(
+    file: &'a str,
+    import_chain: Vec<PathBuf>,
+    options: &'o ParseOptions<'a>,
+) -> std::result::Result<(CornParser<'a, 'o>, Pair<'a, Rule>), Box<pest::error::Error<Rule>>> {
     let rules = AstParser::parse(Rule::config, file);
 
     match rules {
         Ok(mut rules) => {
             let first_block = rules.next().expect("should be at least 1 rule");
+            let line_index = LineIndex::new(file);
 
             match first_block.as_rule() {
                 Rule::assign_block => {
-                    let parser = CornParser::new(Some(first_block));
+                    let parser =
+                        CornParser::new(Some(first_block), line_index, import_chain, options);
                     let object_block = rules.next().expect("should always be an object block");
-                    parser.parse(object_block)
+                    Ok((parser, object_block))
                 }
                 Rule::object => {
-                    let parser = CornParser::new(None);
-                    parser.parse(first_block)
+                    let parser = CornParser::new(None, line_index, import_chain, options);
+                    Ok((parser, first_block))
                 }
                 _ => unreachable!(),
             }
         }
-        Err(error) => Err(Error::ParserError(Box::new(error))),
+        Err(error) => Err(describe_rules(error)),
     }
 }
+
+/// Replaces the names of rules expected by a syntax error
+/// with descriptions which make sense to users,
+/// such as "fallback (`??`)" instead of "fallback".
+pub(crate) fn describe_rules(error: pest::error::Error<Rule>) -> Box<pest::error::Error<Rule>> {
+    Box::new(error.renamed_rules(|rule| match rule {
+        Rule::fallback => String::from("fallback (`??`)"),
+        Rule::cast => String::from("cast (`as`)"),
+        Rule::cast_type => String::from(
+            "cast type (`integer`, `float`, `boolean`, `null`, `array`, `object` or `value`)",
+        ),
+        Rule::required => String::from("required input (`$input!\"message\"`)"),
+        Rule::import => String::from("import (`import \"path\"`)"),
+        rule => format!("{rule:?}"),
+    }))
+}
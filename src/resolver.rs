@@ -0,0 +1,177 @@
+//! Sources for input values which are not defined inside the file itself.
+//!
+//! Resolvers are registered against an input name prefix using [`ParseOptions::resolver`].
+//! By default, only [`EnvResolver`] is registered, for the `$env_` prefix.
+//! Access to environment variables can be restricted using [`ParseOptions::env_access`].
+//!
+//! [`ParseOptions::resolver`]: crate::ParseOptions::resolver
+//! [`ParseOptions::env_access`]: crate::ParseOptions::env_access
+
+use std::collections::{HashMap, HashSet};
+use std::env;
+
+use crate::error::{Error, Result};
+use crate::Value;
+
+/// Resolves the values of inputs which start with a registered prefix.
+///
+/// Resolvers must be `Send + Sync`,
+/// so that [`ParseOptions`](crate::ParseOptions) can be shared between threads.
+///
+/// # Examples
+///
+/// ```rust
+/// use corn::error::Result;
+/// use corn::{InputResolver, ParseOptions, Value};
+///
+/// struct Secrets;
+///
+/// impl InputResolver for Secrets {
+///     fn resolve(&self, name: &str) -> Result<Option<Value<'static>>> {
+///         Ok((name == "DB_PASSWORD").then(|| Value::from("hunter2")))
+///     }
+/// }
+///
+/// let config = ParseOptions::new()
+///     .resolver("$secret_", Secrets)
+///     .parse("{ password = $secret_DB_PASSWORD }")
+///     .unwrap();
+///
+/// let json = serde_json::to_string(&config).unwrap();
+/// assert_eq!(json, "{\"password\":\"hunter2\"}");
+/// ```
+pub trait InputResolver: Send + Sync {
+    /// Gets the value of the input `name`,
+    /// which has had the registered prefix removed.
+    ///
+    /// Returning `None` allows the input to be resolved from elsewhere,
+    /// such as the `let` block.
+    ///
+    /// # Errors
+    ///
+    /// Any error returned stops parsing and is passed to the caller.
+    fn resolve(&self, name: &str) -> Result<Option<Value<'static>>>;
+}
+
+/// Which environment variables configs are allowed to read.
+///
+/// Reading a variable which is not allowed fails with
+/// [`Error::EnvAccessDeniedError`], unless it has a fallback such as `$env_HOME ?? "/"`,
+/// in which case the variable is treated as unset and the fallback is used.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum EnvAccess {
+    /// Any environment variable can be read.
+    #[default]
+    All,
+    /// No environment variables can be read.
+    None,
+    /// Only the environment variables with these exact names can be read.
+    Allowlist(HashSet<String>),
+    /// Only environment variables whose names start with this prefix can be read.
+    Prefix(String),
+}
+
+impl EnvAccess {
+    /// Creates an allowlist from the given variable names.
+    #[must_use]
+    pub fn allowlist<I, S>(names: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self::Allowlist(names.into_iter().map(Into::into).collect())
+    }
+
+    /// Whether the environment variable `name` can be read.
+    #[must_use]
+    pub fn allows(&self, name: &str) -> bool {
+        match self {
+            EnvAccess::All => true,
+            EnvAccess::None => false,
+            EnvAccess::Allowlist(names) => names.contains(name),
+            EnvAccess::Prefix(prefix) => name.starts_with(prefix.as_str()),
+        }
+    }
+}
+
+/// Resolves inputs from the environment variables of the current process.
+///
+/// Reading a variable which is not allowed by its [`EnvAccess`]
+/// fails with [`Error::EnvAccessDeniedError`],
+/// rather than falling back to the `let` block.
+#[derive(Debug, Clone, Default)]
+pub struct EnvResolver {
+    access: EnvAccess,
+}
+
+impl EnvResolver {
+    /// Creates a resolver which can read any environment variable.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a resolver which can only read the variables allowed by `access`.
+    #[must_use]
+    pub fn with_access(access: EnvAccess) -> Self {
+        Self { access }
+    }
+}
+
+impl InputResolver for EnvResolver {
+    fn resolve(&self, name: &str) -> Result<Option<Value<'static>>> {
+        if !self.access.allows(name) {
+            return Err(Error::EnvAccessDeniedError(name.to_string(), None));
+        }
+
+        Ok(env::var(name).ok().map(Value::from))
+    }
+}
+
+/// Resolves inputs from a fixed map of names to values.
+///
+/// Names do not include the prefix the resolver is registered for.
+#[derive(Debug, Clone, Default)]
+pub struct MapResolver {
+    values: HashMap<String, Value<'static>>,
+}
+
+impl MapResolver {
+    #[must_use]
+    pub fn new(values: HashMap<String, Value<'static>>) -> Self {
+        Self { values }
+    }
+}
+
+impl<K, V> FromIterator<(K, V)> for MapResolver
+where
+    K: Into<String>,
+    V: Into<Value<'static>>,
+{
+    fn from_iter<T: IntoIterator<Item = (K, V)>>(iter: T) -> Self {
+        Self::new(
+            iter.into_iter()
+                .map(|(key, value)| (key.into(), value.into()))
+                .collect(),
+        )
+    }
+}
+
+impl InputResolver for MapResolver {
+    fn resolve(&self, name: &str) -> Result<Option<Value<'static>>> {
+        Ok(self.values.get(name).cloned())
+    }
+}
+
+/// Resolves nothing.
+///
+/// Registering this for a prefix means inputs with that prefix
+/// can only be resolved from the file itself or host inputs.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DenyResolver;
+
+impl InputResolver for DenyResolver {
+    fn resolve(&self, _name: &str) -> Result<Option<Value<'static>>> {
+        Ok(None)
+    }
+}
@@ -0,0 +1,732 @@
+use std::borrow::Cow;
+
+use serde::ser::{self, Serialize};
+
+use crate::error::{Error, Result};
+use crate::{Object, Value};
+
+pub(crate) use self::value::ValueSerializer;
+use self::value::{SerializeMap, SerializeVec};
+
+mod value;
+
+pub(crate) const DEFAULT_INDENT: &str = "    ";
+
+/// A serializer which writes Corn text.
+///
+/// Values are first collected into a [`Value`] tree,
+/// which is then written out once complete.
+/// The top-level value must serialize to an object.
+///
+/// # Examples
+///
+/// ```rust
+/// use corn::Serializer;
+/// use serde::Serialize;
+///
+/// #[derive(Serialize)]
+/// struct Config {
+///     server: Server,
+/// }
+///
+/// #[derive(Serialize)]
+/// struct Server {
+///     port: u16,
+/// }
+///
+/// let config = Config { server: Server { port: 8080 } };
+///
+/// let mut serializer = Serializer::new().collapse_paths(true);
+/// config.serialize(&mut serializer).unwrap();
+///
+/// assert_eq!(serializer.into_inner(), "{ server.port = 8080 }");
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Serializer {
+    output: String,
+    /// String used for a single level of indentation.
+    /// If `None`, output is written on a single line.
+    indent: Option<String>,
+    collapse_paths: bool,
+}
+
+impl Serializer {
+    /// Creates a serializer which writes compact, single-line output.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a serializer which writes one pair per line,
+    /// indented with four spaces.
+    #[must_use]
+    pub fn pretty() -> Self {
+        Self {
+            indent: Some(String::from(DEFAULT_INDENT)),
+            ..Self::default()
+        }
+    }
+
+    /// Sets the string used for each level of indentation,
+    /// switching the serializer to multi-line output.
+    #[must_use]
+    pub fn indent(mut self, indent: &str) -> Self {
+        self.indent = Some(indent.to_string());
+        self
+    }
+
+    /// When enabled, objects containing a single key
+    /// are written using dot-notation, for example `a.b.c = 1`.
+    #[must_use]
+    pub fn collapse_paths(mut self, collapse_paths: bool) -> Self {
+        self.collapse_paths = collapse_paths;
+        self
+    }
+
+    /// Consumes the serializer, returning the written output.
+    #[must_use]
+    pub fn into_inner(self) -> String {
+        self.output
+    }
+
+    fn write_document(&mut self, value: &Value) -> Result<()> {
+        match value {
+            Value::Object(obj) => self.write_object(obj, 0),
+            _ => Err(Error::SerializationError(format!(
+                "expected top-level value to be an object, found {value}"
+            ))),
+        }
+    }
+
+    fn write_indent(&mut self, level: usize) {
+        if let Some(indent) = &self.indent {
+            for _ in 0..level {
+                self.output.push_str(indent);
+            }
+        }
+    }
+
+    fn write_value(&mut self, value: &Value, level: usize) -> Result<()> {
+        match value {
+            Value::Object(obj) => self.write_object(obj, level)?,
+            Value::Array(arr) => self.write_array(arr, level)?,
+            Value::String(val) => self.write_string(val, level)?,
+            Value::Integer(val) => self.output.push_str(&val.to_string()),
+            Value::Float(val) => self.output.push_str(&format_float(*val)?),
+            Value::Boolean(val) => self.output.push_str(&val.to_string()),
+            Value::Null(_) => self.output.push_str("null"),
+        }
+
+        Ok(())
+    }
+
+    /// Writes each pair in `obj`.
+    /// `level` is the indentation level of the line the object opens on.
+    fn write_object(&mut self, obj: &Object, level: usize) -> Result<()> {
+        if obj.is_empty() {
+            self.output.push_str("{}");
+            return Ok(());
+        }
+
+        let pretty = self.indent.is_some();
+
+        self.output.push('{');
+        for (key, value) in obj {
+            if pretty {
+                self.output.push('\n');
+                self.write_indent(level + 1);
+            } else {
+                self.output.push(' ');
+            }
+
+            self.write_pair(key, value, level + 1)?;
+        }
+
+        if pretty {
+            self.output.push('\n');
+            self.write_indent(level);
+        } else {
+            self.output.push(' ');
+        }
+        self.output.push('}');
+
+        Ok(())
+    }
+
+    fn write_pair(&mut self, key: &str, mut value: &Value, level: usize) -> Result<()> {
+        self.output.push_str(&format_key(key)?);
+
+        if self.collapse_paths {
+            while let Value::Object(obj) = value {
+                if obj.len() != 1 {
+                    break;
+                }
+
+                let (key, child) = obj.first().expect("object should contain one entry");
+                self.output.push('.');
+                self.output.push_str(&format_key(key)?);
+                value = child;
+            }
+        }
+
+        self.output.push_str(" = ");
+        self.write_value(value, level)
+    }
+
+    /// Writes each element in `arr`.
+    /// Arrays containing only scalar values are always written on a single line.
+    fn write_array(&mut self, arr: &[Value], level: usize) -> Result<()> {
+        if arr.is_empty() {
+            self.output.push_str("[]");
+            return Ok(());
+        }
+
+        let multiline = self.indent.is_some()
+            && arr
+                .iter()
+                .any(|value| matches!(value, Value::Object(_) | Value::Array(_)));
+
+        self.output.push('[');
+        for value in arr {
+            if multiline {
+                self.output.push('\n');
+                self.write_indent(level + 1);
+            } else {
+                self.output.push(' ');
+            }
+
+            self.write_value(value, level + 1)?;
+        }
+
+        if multiline {
+            self.output.push('\n');
+            self.write_indent(level);
+        } else {
+            self.output.push(' ');
+        }
+        self.output.push(']');
+
+        Ok(())
+    }
+
+    /// Writes a string literal.
+    ///
+    /// Strings containing newlines are written across multiple lines,
+    /// taking into account the indentation the parser trims from them.
+    fn write_string(&mut self, string: &str, level: usize) -> Result<()> {
+        self.output.push('"');
+
+        if string.contains('\n') {
+            let lines = string.split('\n').collect::<Vec<_>>();
+            let trailing_newline = string.ends_with('\n');
+
+            let content_lines = if trailing_newline {
+                &lines[..lines.len() - 1]
+            } else {
+                &lines[..]
+            };
+
+            // the parser removes the smallest common indentation,
+            // so at least one line must be unindented to preserve it.
+            let representable = !string.contains("\r\n")
+                && (trailing_newline
+                    || content_lines.iter().any(|line| {
+                        line.is_empty() || !line.starts_with(|c: char| c.is_ascii_whitespace())
+                    }));
+
+            if !representable {
+                return Err(Error::SerializationError(format!(
+                    "string `{}` cannot be represented without losing whitespace",
+                    string.escape_debug()
+                )));
+            }
+
+            // compact output still needs indentation here,
+            // as the closing line must not be empty to keep a trailing newline.
+            let indent = self
+                .indent
+                .as_deref()
+                .unwrap_or(DEFAULT_INDENT)
+                .repeat(level + 1);
+
+            for line in content_lines {
+                self.output.push('\n');
+                self.output.push_str(&indent);
+                self.write_escaped(line);
+            }
+
+            if trailing_newline {
+                self.output.push('\n');
+                self.output.push_str(&indent);
+            }
+        } else {
+            self.write_escaped(string);
+        }
+
+        self.output.push('"');
+
+        Ok(())
+    }
+
+    /// Writes `string` with any characters
+    /// which have special meaning inside string literals escaped.
+    fn write_escaped(&mut self, string: &str) {
+        let mut chars = string.chars().peekable();
+
+        while let Some(char) = chars.next() {
+            match char {
+                '"' => self.output.push_str("\\\""),
+                '\\' => self.output.push_str("\\\\"),
+                '\r' => self.output.push_str("\\r"),
+                '\t' => self.output.push_str("\\t"),
+                '\n' => self.output.push_str("\\n"),
+                // only escape where it would otherwise start an input
+                '$' if chars
+                    .peek()
+                    .is_some_and(|next| next.is_ascii_alphabetic() || *next == '_') =>
+                {
+                    self.output.push_str("\\$");
+                }
+                char if char.is_control() => {
+                    self.output.push_str(&format!("\\u{:04x}", u32::from(char)));
+                }
+                char => self.output.push(char),
+            }
+        }
+    }
+}
+
+/// Writes a single value, rather than a whole document.
+///
+/// If `indent` is set, nested objects are written across multiple lines,
+/// starting at an indentation level of zero.
+pub(crate) fn value_to_string(value: &Value, indent: Option<&str>) -> Result<String> {
+    let mut serializer = Serializer {
+        indent: indent.map(ToString::to_string),
+        ..Serializer::default()
+    };
+
+    serializer.write_value(value, 0)?;
+    Ok(serializer.into_inner())
+}
+
+/// Formats `key` as a path segment,
+/// quoting it if it cannot be written as-is.
+pub(crate) fn format_key(key: &str) -> Result<Cow<'_, str>> {
+    let needs_quotes = key.is_empty()
+        || key.starts_with('\'')
+        || key.starts_with("//")
+        || key.contains([' ', '\t', '\r', '\n', '=', '.']);
+
+    if !needs_quotes {
+        return Ok(Cow::Borrowed(key));
+    }
+
+    if key.is_empty() || key.contains('\\') {
+        return Err(Error::SerializationError(format!(
+            "key `{}` cannot be represented in Corn",
+            key.escape_debug()
+        )));
+    }
+
+    Ok(Cow::Owned(format!("'{}'", key.replace('\'', "\\'"))))
+}
+
+/// Formats `float` so that it always contains a decimal point,
+/// as required by the grammar.
+fn format_float(float: f64) -> Result<String> {
+    if !float.is_finite() {
+        return Err(Error::SerializationError(format!(
+            "float `{float}` cannot be represented in Corn"
+        )));
+    }
+
+    let string = format!("{float:?}");
+
+    if string.contains('.') {
+        return Ok(string);
+    }
+
+    match string.split_once('e') {
+        Some((mantissa, exponent)) => Ok(format!("{mantissa}.0e{exponent}")),
+        None => Ok(format!("{string}.0")),
+    }
+}
+
+macro_rules! serialize_scalar {
+    ($($method:ident($ty:ty)),+) => {
+        $(
+            fn $method(self, v: $ty) -> Result<()> {
+                let value = ValueSerializer.$method(v)?;
+                self.write_document(&value)
+            }
+        )+
+    };
+}
+
+impl<'a> ser::Serializer for &'a mut Serializer {
+    type Ok = ();
+    type Error = Error;
+
+    type SerializeSeq = Compound<'a, SerializeVec>;
+    type SerializeTuple = Compound<'a, SerializeVec>;
+    type SerializeTupleStruct = Compound<'a, SerializeVec>;
+    type SerializeTupleVariant = Compound<'a, SerializeVec>;
+    type SerializeMap = Compound<'a, SerializeMap>;
+    type SerializeStruct = Compound<'a, SerializeMap>;
+    type SerializeStructVariant = Compound<'a, SerializeMap>;
+
+    serialize_scalar!(
+        serialize_bool(bool),
+        serialize_i8(i8),
+        serialize_i16(i16),
+        serialize_i32(i32),
+        serialize_i64(i64),
+        serialize_i128(i128),
+        serialize_u8(u8),
+        serialize_u16(u16),
+        serialize_u32(u32),
+        serialize_u64(u64),
+        serialize_u128(u128),
+        serialize_f32(f32),
+        serialize_f64(f64),
+        serialize_char(char),
+        serialize_str(&str),
+        serialize_bytes(&[u8]),
+        serialize_unit_struct(&'static str)
+    );
+
+    fn serialize_none(self) -> Result<()> {
+        let value = ValueSerializer.serialize_none()?;
+        self.write_document(&value)
+    }
+
+    fn serialize_some<T>(self, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<()> {
+        let value = ValueSerializer.serialize_unit()?;
+        self.write_document(&value)
+    }
+
+    fn serialize_unit_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+    ) -> Result<()> {
+        let value = ValueSerializer.serialize_unit_variant(name, variant_index, variant)?;
+        self.write_document(&value)
+    }
+
+    fn serialize_newtype_struct<T>(self, _name: &'static str, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T>(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        let value =
+            ValueSerializer.serialize_newtype_variant(name, variant_index, variant, value)?;
+        self.write_document(&value)
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq> {
+        Ok(Compound {
+            inner: ValueSerializer.serialize_seq(len)?,
+            ser: self,
+        })
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple> {
+        Ok(Compound {
+            inner: ValueSerializer.serialize_tuple(len)?,
+            ser: self,
+        })
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleStruct> {
+        Ok(Compound {
+            inner: ValueSerializer.serialize_tuple_struct(name, len)?,
+            ser: self,
+        })
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleVariant> {
+        Ok(Compound {
+            inner: ValueSerializer.serialize_tuple_variant(name, variant_index, variant, len)?,
+            ser: self,
+        })
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap> {
+        Ok(Compound {
+            inner: ValueSerializer.serialize_map(len)?,
+            ser: self,
+        })
+    }
+
+    fn serialize_struct(self, name: &'static str, len: usize) -> Result<Self::SerializeStruct> {
+        Ok(Compound {
+            inner: ValueSerializer.serialize_struct(name, len)?,
+            ser: self,
+        })
+    }
+
+    fn serialize_struct_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStructVariant> {
+        Ok(Compound {
+            inner: ValueSerializer.serialize_struct_variant(name, variant_index, variant, len)?,
+            ser: self,
+        })
+    }
+}
+
+/// Collects a compound value
+/// and writes it once complete.
+pub struct Compound<'a, T> {
+    ser: &'a mut Serializer,
+    inner: T,
+}
+
+impl<T> ser::SerializeSeq for Compound<'_, T>
+where
+    T: ser::SerializeSeq<Ok = Value<'static>, Error = Error>,
+{
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<U>(&mut self, value: &U) -> Result<()>
+    where
+        U: ?Sized + Serialize,
+    {
+        self.inner.serialize_element(value)
+    }
+
+    fn end(self) -> Result<()> {
+        let value = self.inner.end()?;
+        self.ser.write_document(&value)
+    }
+}
+
+impl<T> ser::SerializeTuple for Compound<'_, T>
+where
+    T: ser::SerializeTuple<Ok = Value<'static>, Error = Error>,
+{
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<U>(&mut self, value: &U) -> Result<()>
+    where
+        U: ?Sized + Serialize,
+    {
+        self.inner.serialize_element(value)
+    }
+
+    fn end(self) -> Result<()> {
+        let value = self.inner.end()?;
+        self.ser.write_document(&value)
+    }
+}
+
+impl<T> ser::SerializeTupleStruct for Compound<'_, T>
+where
+    T: ser::SerializeTupleStruct<Ok = Value<'static>, Error = Error>,
+{
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<U>(&mut self, value: &U) -> Result<()>
+    where
+        U: ?Sized + Serialize,
+    {
+        self.inner.serialize_field(value)
+    }
+
+    fn end(self) -> Result<()> {
+        let value = self.inner.end()?;
+        self.ser.write_document(&value)
+    }
+}
+
+impl<T> ser::SerializeTupleVariant for Compound<'_, T>
+where
+    T: ser::SerializeTupleVariant<Ok = Value<'static>, Error = Error>,
+{
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<U>(&mut self, value: &U) -> Result<()>
+    where
+        U: ?Sized + Serialize,
+    {
+        self.inner.serialize_field(value)
+    }
+
+    fn end(self) -> Result<()> {
+        let value = self.inner.end()?;
+        self.ser.write_document(&value)
+    }
+}
+
+impl<T> ser::SerializeMap for Compound<'_, T>
+where
+    T: ser::SerializeMap<Ok = Value<'static>, Error = Error>,
+{
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_key<U>(&mut self, key: &U) -> Result<()>
+    where
+        U: ?Sized + Serialize,
+    {
+        self.inner.serialize_key(key)
+    }
+
+    fn serialize_value<U>(&mut self, value: &U) -> Result<()>
+    where
+        U: ?Sized + Serialize,
+    {
+        self.inner.serialize_value(value)
+    }
+
+    fn end(self) -> Result<()> {
+        let value = self.inner.end()?;
+        self.ser.write_document(&value)
+    }
+}
+
+impl<T> ser::SerializeStruct for Compound<'_, T>
+where
+    T: ser::SerializeStruct<Ok = Value<'static>, Error = Error>,
+{
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<U>(&mut self, key: &'static str, value: &U) -> Result<()>
+    where
+        U: ?Sized + Serialize,
+    {
+        self.inner.serialize_field(key, value)
+    }
+
+    fn end(self) -> Result<()> {
+        let value = self.inner.end()?;
+        self.ser.write_document(&value)
+    }
+}
+
+impl<T> ser::SerializeStructVariant for Compound<'_, T>
+where
+    T: ser::SerializeStructVariant<Ok = Value<'static>, Error = Error>,
+{
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<U>(&mut self, key: &'static str, value: &U) -> Result<()>
+    where
+        U: ?Sized + Serialize,
+    {
+        self.inner.serialize_field(key, value)
+    }
+
+    fn end(self) -> Result<()> {
+        let value = self.inner.end()?;
+        self.ser.write_document(&value)
+    }
+}
+
+/// Serializes `value` as a compact, single-line Corn string.
+///
+/// # Examples
+///
+/// ```rust
+/// let config = corn::parse("{ foo.bar = [ 1 2 ] }").unwrap();
+/// let corn = corn::to_string(&config).unwrap();
+///
+/// assert_eq!(corn, "{ foo = { bar = [ 1 2 ] } }");
+/// ```
+///
+/// # Errors
+///
+/// Will fail if `value` does not serialize to an object,
+/// or contains data which cannot be represented in Corn,
+/// such as non-string keys or non-finite floats.
+pub fn to_string<T>(value: &T) -> Result<String>
+where
+    T: ?Sized + Serialize,
+{
+    let mut serializer = Serializer::new();
+    value.serialize(&mut serializer)?;
+    Ok(serializer.into_inner())
+}
+
+/// Serializes `value` as a Corn string,
+/// writing one pair per line.
+///
+/// # Errors
+///
+/// Will fail if `value` does not serialize to an object,
+/// or contains data which cannot be represented in Corn,
+/// such as non-string keys or non-finite floats.
+pub fn to_string_pretty<T>(value: &T) -> Result<String>
+where
+    T: ?Sized + Serialize,
+{
+    let mut serializer = Serializer::pretty();
+    value.serialize(&mut serializer)?;
+    Ok(serializer.into_inner())
+}
+
+/// Serializes `value` into a [`Value`] tree.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::collections::HashMap;
+///
+/// let ports = HashMap::from([("http", 80), ("https", 443)]);
+/// let value = corn::to_value(&ports).unwrap();
+///
+/// assert!(matches!(value, corn::Value::Object(obj) if obj.len() == 2));
+/// ```
+///
+/// # Errors
+///
+/// Will fail if `value` contains data which cannot be represented in Corn,
+/// such as non-scalar keys or integers wider than 128 bits.
+pub fn to_value<T>(value: &T) -> Result<Value<'static>>
+where
+    T: ?Sized + Serialize,
+{
+    value.serialize(ValueSerializer)
+}
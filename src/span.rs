@@ -0,0 +1,189 @@
+use indexmap::IndexMap;
+use std::borrow::Cow;
+use std::fmt::{Display, Formatter};
+use std::ops::Range;
+
+use crate::{Object, Value};
+
+/// A single location in the source input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Position {
+    /// Byte offset from the start of the input.
+    pub offset: usize,
+    /// 1-based line number.
+    pub line: usize,
+    /// 1-based column number, counted in characters.
+    pub column: usize,
+}
+
+impl Display for Position {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
+/// The region of the source input a token was parsed from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Span {
+    /// Position of the first character.
+    pub start: Position,
+    /// Position immediately after the last character.
+    pub end: Position,
+}
+
+impl Span {
+    /// The byte range covered by this span,
+    /// suitable for slicing the original input.
+    #[must_use]
+    pub fn range(&self) -> Range<usize> {
+        self.start.offset..self.end.offset
+    }
+}
+
+impl Display for Span {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.start)
+    }
+}
+
+/// A value alongside the span of source it was parsed from.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Spanned<T> {
+    pub span: Span,
+    pub value: T,
+}
+
+impl<T> Spanned<T> {
+    pub fn new(span: Span, value: T) -> Self {
+        Self { span, value }
+    }
+}
+
+/// A key/value entry inside a [`SpannedValue::Object`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpannedEntry<'a> {
+    /// Span of the key segment which first created this entry.
+    pub key: Span,
+    pub value: Spanned<SpannedValue<'a>>,
+}
+
+/// A map of keys to their spanned entries.
+pub type SpannedObject<'a> = IndexMap<Cow<'a, str>, SpannedEntry<'a>>;
+
+/// A parsed value tree which mirrors [`Value`],
+/// where every node keeps the location it came from.
+///
+/// Values resolved from an input reference carry the span of the reference,
+/// while any children keep the spans from where the input was declared.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SpannedValue<'a> {
+    Object(SpannedObject<'a>),
+    Array(Vec<Spanned<SpannedValue<'a>>>),
+    String(Cow<'a, str>),
+    Integer(i128),
+    Float(f64),
+    Boolean(bool),
+    Null,
+}
+
+impl<'a> SpannedValue<'a> {
+    /// Discards all span information, producing a plain [`Value`].
+    #[must_use]
+    pub fn into_value(self) -> Value<'a> {
+        match self {
+            SpannedValue::Object(obj) => Value::Object(
+                obj.into_iter()
+                    .map(|(key, entry)| (key, entry.value.value.into_value()))
+                    .collect::<Object>(),
+            ),
+            SpannedValue::Array(arr) => {
+                Value::Array(arr.into_iter().map(|v| v.value.into_value()).collect())
+            }
+            SpannedValue::String(val) => Value::String(val),
+            SpannedValue::Integer(val) => Value::Integer(val),
+            SpannedValue::Float(val) => Value::Float(val),
+            SpannedValue::Boolean(val) => Value::Boolean(val),
+            SpannedValue::Null => Value::Null(None),
+        }
+    }
+
+    /// Converts a plain [`Value`] into a spanned tree,
+    /// attributing every node to `span`.
+    #[must_use]
+    pub fn from_value(value: Value<'a>, span: Span) -> Spanned<Self> {
+        let value = match value {
+            Value::Object(obj) => SpannedValue::Object(
+                obj.into_iter()
+                    .map(|(key, value)| {
+                        (
+                            key,
+                            SpannedEntry {
+                                key: span,
+                                value: Self::from_value(value, span),
+                            },
+                        )
+                    })
+                    .collect(),
+            ),
+            Value::Array(arr) => SpannedValue::Array(
+                arr.into_iter()
+                    .map(|value| Self::from_value(value, span))
+                    .collect(),
+            ),
+            Value::String(val) => SpannedValue::String(val),
+            Value::Integer(val) => SpannedValue::Integer(val),
+            Value::Float(val) => SpannedValue::Float(val),
+            Value::Boolean(val) => SpannedValue::Boolean(val),
+            Value::Null(_) => SpannedValue::Null,
+        };
+
+        Spanned::new(span, value)
+    }
+}
+
+impl<'a> From<Spanned<SpannedValue<'a>>> for Value<'a> {
+    fn from(value: Spanned<SpannedValue<'a>>) -> Self {
+        value.value.into_value()
+    }
+}
+
+/// Lookup table for converting byte offsets
+/// into line/column positions.
+#[derive(Debug)]
+pub(crate) struct LineIndex<'a> {
+    input: &'a str,
+    line_starts: Vec<usize>,
+}
+
+impl<'a> LineIndex<'a> {
+    pub fn new(input: &'a str) -> Self {
+        let line_starts = std::iter::once(0)
+            .chain(input.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+
+        Self { input, line_starts }
+    }
+
+    pub fn position(&self, offset: usize) -> Position {
+        let line = match self.line_starts.binary_search(&offset) {
+            Ok(line) => line,
+            Err(line) => line - 1,
+        };
+
+        let line_start = self.line_starts[line];
+        let column = self.input[line_start..offset].chars().count() + 1;
+
+        Position {
+            offset,
+            line: line + 1,
+            column,
+        }
+    }
+
+    pub fn span(&self, span: &pest::Span<'_>) -> Span {
+        Span {
+            start: self.position(span.start()),
+            end: self.position(span.end()),
+        }
+    }
+}
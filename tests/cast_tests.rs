@@ -0,0 +1,112 @@
+use corn::error::Error;
+use corn::Value;
+
+use common::{options, parse};
+
+mod common;
+
+fn parse_err(corn: &str) -> Error {
+    options().parse(corn).unwrap_err()
+}
+
+#[test]
+fn scalars() {
+    assert_eq!(
+        parse("{ port = $env_PORT as integer hex = $env_HEX as integer ratio = $env_RATIO as float debug = $env_DEBUG as boolean nothing = $env_NOTHING as null }"),
+        r#"{"port":8080,"hex":255,"ratio":1.5,"debug":true,"nothing":null}"#
+    );
+}
+
+#[test]
+fn integer_to_float() {
+    assert_eq!(
+        parse("let { $count = 3 } in { whole = $env_WHOLE as float count = $count as float }"),
+        r#"{"whole":2.0,"count":3.0}"#
+    );
+}
+
+#[test]
+fn compound_values() {
+    assert_eq!(
+        parse("{ hosts = $env_HOSTS as array limits = $env_LIMITS as object }"),
+        r#"{"hosts":["a","b"],"limits":{"cpu":2,"memory":{"max":"1Gi"}}}"#
+    );
+}
+
+#[test]
+fn any_value() {
+    assert_eq!(
+        parse("{ port = $env_PORT as value hosts = [ $env_HOSTS as value ] }"),
+        r#"{"port":8080,"hosts":[["a","b"]]}"#
+    );
+}
+
+#[test]
+fn cast_in_let_block() {
+    assert_eq!(
+        parse("let { $port = $env_PORT as integer } in { server.port = $port }"),
+        r#"{"server":{"port":8080}}"#
+    );
+}
+
+#[test]
+fn uncast_input_is_string() {
+    assert_eq!(parse("{ port = $env_PORT }"), r#"{"port":"8080"}"#);
+}
+
+#[test]
+fn invalid_text() {
+    let err = parse_err("{ port = $env_NAME as integer }");
+
+    assert!(matches!(err, Error::InvalidCastError(..)));
+    assert_eq!(
+        err.to_string(),
+        "failed to convert input `$env_NAME` to integer: `api` is not a valid integer"
+    );
+}
+
+#[test]
+fn wrong_type() {
+    let err = parse_err("{ port = $env_RATIO as integer }");
+    assert!(matches!(err, Error::InvalidCastError(..)));
+
+    let err = parse_err("{ name = $env_NAME as value }");
+    assert_eq!(
+        err.to_string(),
+        "failed to convert input `$env_NAME` to value: `api` is not a valid Corn value"
+    );
+}
+
+#[test]
+fn wrong_type_non_string() {
+    let err = parse_err("let { $obj = { a = 1 } } in { port = $obj as integer }");
+
+    assert_eq!(
+        err.to_string(),
+        "failed to convert input `$obj` to integer: expected integer but found object"
+    );
+}
+
+#[test]
+fn inputs_in_text() {
+    let err = parse_err("{ port = $env_REFERENCE as integer }");
+    assert!(matches!(err, Error::InvalidCastError(..)));
+}
+
+#[test]
+fn out_of_range_text() {
+    let err = parse_err("{ port = $env_HUGE as integer }");
+    assert!(matches!(err, Error::InvalidCastError(..)));
+}
+
+#[test]
+fn unresolved_input() {
+    let err = parse_err("{ port = $env_MISSING as integer }");
+    assert!(matches!(err, Error::InputResolveError(..)));
+}
+
+#[test]
+fn as_key_still_valid() {
+    let config = corn::parse("let { $foo = 1 } in { a = $foo as = 2 }").unwrap();
+    assert!(matches!(config, Value::Object(obj) if obj.len() == 2));
+}
@@ -0,0 +1,38 @@
+//! Helpers shared by the integration tests.
+//! Not every test file uses every helper.
+#![allow(dead_code)]
+
+use corn::resolver::MapResolver;
+use corn::{ParseOptions, Value};
+
+/// Creates options which resolve `$env_` inputs from a fixed set of variables,
+/// so that tests do not depend on the environment they run in.
+pub fn options() -> ParseOptions<'static> {
+    ParseOptions::new().resolver(
+        "$env_",
+        MapResolver::from_iter([
+            ("PORT", "8080"),
+            ("HEX", "0xFF"),
+            ("RATIO", "1.5"),
+            ("WHOLE", "2"),
+            ("DEBUG", "true"),
+            ("NOTHING", "null"),
+            ("HOSTS", "[ \"a\" \"b\" ]"),
+            ("LIMITS", "{ cpu = 2 memory.max = \"1Gi\" }"),
+            ("NAME", "api"),
+            ("REFERENCE", "$env_PORT"),
+            ("HUGE", "999999999999999999999999999999999999999999"),
+            ("LOG_LEVEL", "debug"),
+            ("DATABASE_URL", "postgres://db"),
+        ]),
+    )
+}
+
+/// Parses `corn` using [`options`], returning the value as JSON.
+pub fn parse(corn: &str) -> String {
+    to_json(&options().parse(corn).unwrap())
+}
+
+pub fn to_json(value: &Value) -> String {
+    serde_json::to_string(value).unwrap()
+}
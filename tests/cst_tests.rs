@@ -0,0 +1,164 @@
+use corn::cst::{self, SyntaxKind, SyntaxNode};
+use paste::paste;
+use std::fs;
+
+macro_rules! generate_round_trip_tests {
+    ($($test_name:ident),+) => {
+        $(
+            paste! {
+                #[test]
+                fn [<round_trip_ $test_name>]() {
+                    let test_name = stringify!($test_name);
+                    let root_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+
+                    let input = fs::read_to_string(format!("{root_dir}/assets/inputs/{test_name}.corn")).unwrap();
+                    let document = cst::parse(&input).unwrap();
+
+                    assert_eq!(document.to_string(), input);
+                    assert_eq!(document.text_len(), input.len());
+                }
+            }
+        )+
+    };
+}
+
+generate_round_trip_tests!(
+    array,
+    basic,
+    basic_empty_let,
+    boolean,
+    chained,
+    chained_complex,
+    char,
+    comment,
+    compact,
+    complex,
+    complex_keys,
+    environment_variable,
+    float,
+    input,
+    input_references_input,
+    integer,
+    invalid_input,
+    invalid_nesting,
+    invalid_spread,
+    mixed_array,
+    null,
+    null_in_array,
+    object,
+    object_in_array,
+    quoted_keys,
+    readme_example,
+    spread,
+    string,
+    string_interpolation,
+    string_multiline,
+    value_after_table,
+    very_compact
+);
+
+fn kinds(node: &SyntaxNode) -> Vec<SyntaxKind> {
+    node.children().iter().map(|child| child.kind()).collect()
+}
+
+#[test]
+fn invalid_syntax() {
+    let input = "this is not valid corn config";
+
+    let cst_err = cst::parse(input).unwrap_err().to_string();
+    let parse_err = corn::parse(input).unwrap_err().to_string();
+
+    assert_eq!(cst_err, parse_err);
+}
+
+#[test]
+fn structure() {
+    let input = "let { $foo = 1 } in {\n  // comment\n  a.'b.c' = [ ..$foo ]\n}";
+    let document = cst::parse(input).unwrap();
+
+    assert_eq!(
+        kinds(&document),
+        vec![
+            SyntaxKind::AssignBlock,
+            SyntaxKind::Whitespace,
+            SyntaxKind::Object
+        ]
+    );
+
+    let object = document.child_node(SyntaxKind::Object).unwrap();
+    assert_eq!(
+        kinds(object),
+        vec![
+            SyntaxKind::LBrace,
+            SyntaxKind::Whitespace,
+            SyntaxKind::Comment,
+            SyntaxKind::Whitespace,
+            SyntaxKind::Pair,
+            SyntaxKind::Whitespace,
+            SyntaxKind::RBrace
+        ]
+    );
+
+    let path = object
+        .child_node(SyntaxKind::Pair)
+        .and_then(|pair| pair.child_node(SyntaxKind::Path))
+        .unwrap();
+    assert_eq!(
+        kinds(path),
+        vec![
+            SyntaxKind::PathSegment,
+            SyntaxKind::Dot,
+            SyntaxKind::QuotedPathSegment
+        ]
+    );
+}
+
+#[test]
+fn literal_spelling() {
+    let input = "{ a = 0xFF b = 1_000 c = 1.5E+3 d = \"\\u0061\" }";
+    let document = cst::parse(input).unwrap();
+
+    let literals = document
+        .descendant_tokens()
+        .filter(|token| {
+            matches!(
+                token.kind(),
+                SyntaxKind::Integer | SyntaxKind::Float | SyntaxKind::String
+            )
+        })
+        .map(|token| token.text())
+        .collect::<Vec<_>>();
+
+    assert_eq!(literals, vec!["0xFF", "1_000", "1.5E+3", "\"\\u0061\""]);
+}
+
+#[test]
+fn trailing_trivia() {
+    let input = "\n// leading\n{}\n\n// trailing\n";
+    let document = cst::parse(input).unwrap();
+
+    assert_eq!(document.to_string(), input);
+}
+
+#[test]
+fn import() {
+    let input = "let {\n  $shared = import \"./shared.corn\" // shared config\n} in { ..$shared }";
+    let document = cst::parse(input).unwrap();
+
+    assert_eq!(document.to_string(), input);
+
+    let assignment = document
+        .child_node(SyntaxKind::AssignBlock)
+        .and_then(|block| block.child_node(SyntaxKind::Assignment))
+        .unwrap();
+    let import = assignment.child_node(SyntaxKind::Import).unwrap();
+
+    assert_eq!(
+        kinds(import),
+        vec![
+            SyntaxKind::ImportKeyword,
+            SyntaxKind::Whitespace,
+            SyntaxKind::String
+        ]
+    );
+}
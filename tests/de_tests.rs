@@ -1,6 +1,8 @@
-use corn::from_str;
+use corn::error::Error;
+use corn::{from_str, Deserializer, DeserializerOptions, ParseOptions, UnknownKeys, Value};
 use paste::paste;
 use serde::Deserialize;
+use std::collections::HashMap;
 use std::fs;
 
 macro_rules! generate_eq_tests {
@@ -294,6 +296,14 @@ struct Integer {
     baz: i64,
 }
 
+#[derive(Deserialize, Debug, PartialEq)]
+struct LargeInteger {
+    mask: u64,
+    snowflake: u64,
+    max: i128,
+    min: i128,
+}
+
 #[derive(Deserialize, Debug, PartialEq)]
 struct MixedArray {
     foo: (u8, String, bool),
@@ -460,6 +470,7 @@ generate_eq_tests!(
     (input, Input),
     (input_references_input, Basic),
     (integer, Integer),
+    (large_integer, LargeInteger),
     (mixed_array, MixedArray),
     (null, Null),
     (null_in_array, NullInArray),
@@ -594,3 +605,476 @@ fn null_unit() {
 
     assert_eq!(config, json_config);
 }
+
+#[test]
+fn large_integer_out_of_range() {
+    #[derive(Deserialize, Debug)]
+    struct Unsigned {
+        #[allow(dead_code)]
+        foo: u64,
+    }
+
+    #[derive(Deserialize, Debug)]
+    struct Signed {
+        #[allow(dead_code)]
+        foo: i64,
+    }
+
+    assert!(from_str::<Unsigned>("{ foo = -1 }").is_err());
+    assert!(from_str::<Unsigned>("{ foo = 18446744073709551616 }").is_err());
+    assert!(from_str::<Signed>("{ foo = 0xFFFFFFFFFFFFFFFF }").is_err());
+}
+
+#[test]
+fn large_integer_any() {
+    let config = from_str::<serde_json::Value>("{ foo = 0xFFFFFFFFFFFFFFFF bar = -1 }").unwrap();
+    assert_eq!(config["foo"].as_u64(), Some(u64::MAX));
+    assert_eq!(config["bar"].as_i64(), Some(-1));
+}
+
+#[test]
+fn integer_out_of_range() {
+    #[derive(Deserialize, Debug)]
+    struct Server {
+        #[allow(dead_code)]
+        port: u16,
+    }
+
+    #[derive(Deserialize, Debug)]
+    struct Config {
+        #[allow(dead_code)]
+        server: Server,
+    }
+
+    let err = from_str::<Config>("{ server.port = 70000 }").unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "failed to deserialize input: integer `70000` is out of range for u16 at `server.port`"
+    );
+
+    let err = from_str::<HashMap<String, u64>>("{ timeout = -1 }").unwrap_err();
+    assert!(err
+        .to_string()
+        .ends_with("integer `-1` is out of range for u64 at `timeout`"));
+}
+
+#[test]
+fn narrow_integers_in_range() {
+    let config = from_str::<HashMap<String, (i8, u8, i16, u16, i32, u32)>>(
+        "{ min = [ -128 0 -32768 0 -2147483648 0 ] max = [ 127 255 32767 65535 2147483647 4294967295 ] }",
+    )
+    .unwrap();
+
+    assert_eq!(config["min"], (i8::MIN, 0, i16::MIN, 0, i32::MIN, 0));
+    assert_eq!(
+        config["max"],
+        (i8::MAX, u8::MAX, i16::MAX, u16::MAX, i32::MAX, u32::MAX)
+    );
+}
+
+#[test]
+fn out_of_range_path() {
+    #[derive(Deserialize, Debug)]
+    enum Listener {
+        #[allow(dead_code)]
+        Tcp { port: u16 },
+    }
+
+    #[derive(Deserialize, Debug)]
+    struct Config {
+        #[allow(dead_code)]
+        listeners: Vec<Listener>,
+        #[allow(dead_code)]
+        ratio: Option<f32>,
+    }
+
+    let err = from_str::<Config>(
+        "{ listeners = [ { Tcp.port = 80 } { Tcp.port = 443 } { Tcp.port = -1 } ] }",
+    )
+    .unwrap_err();
+    assert!(err.to_string().ends_with("at `listeners[2].Tcp.port`"));
+
+    let err = from_str::<Config>("{ listeners = [] ratio = 1.0e300 }").unwrap_err();
+    assert!(err
+        .to_string()
+        .ends_with("float `1e300` is out of range for f32 at `ratio`"));
+}
+
+#[derive(Deserialize, Debug, PartialEq)]
+struct Timeouts {
+    connect: f64,
+    read: f32,
+}
+
+#[test]
+fn integer_to_float() {
+    let config = from_str::<Timeouts>("{ connect = 30 read = -5 }").unwrap();
+    assert_eq!(
+        config,
+        Timeouts {
+            connect: 30.0,
+            read: -5.0
+        }
+    );
+}
+
+#[test]
+fn float_to_integer() {
+    let options = DeserializerOptions::new().float_to_int(true);
+
+    let config = options
+        .from_str::<HashMap<String, (u8, i64, i128)>>("{ foo = [ 3.0 -2.0 1.0e20 ] }")
+        .unwrap();
+    assert_eq!(config["foo"], (3, -2, 100_000_000_000_000_000_000));
+
+    assert!(from_str::<HashMap<String, u8>>("{ foo = 3.0 }").is_err());
+}
+
+#[test]
+fn float_to_integer_lossy() {
+    let options = DeserializerOptions::new().float_to_int(true);
+
+    let err = options
+        .from_str::<HashMap<String, i32>>("{ foo = 3.5 }")
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "failed to deserialize input: float `3.5` cannot be converted to an integer without losing precision at `foo`"
+    );
+
+    let err = options
+        .from_str::<HashMap<String, u8>>("{ foo = 300.0 }")
+        .unwrap_err();
+    assert!(err
+        .to_string()
+        .ends_with("integer `300` is out of range for u8 at `foo`"));
+
+    assert!(options
+        .from_str::<HashMap<String, i128>>("{ foo = 1.0e300 }")
+        .is_err());
+}
+
+#[derive(Deserialize, Debug)]
+#[allow(dead_code)]
+struct Listener {
+    port: u16,
+    host: String,
+}
+
+#[derive(Deserialize, Debug)]
+#[allow(dead_code)]
+struct Server {
+    listeners: Vec<Listener>,
+}
+
+#[derive(Deserialize, Debug)]
+#[allow(dead_code)]
+struct ServerConfig {
+    server: Server,
+}
+
+#[test]
+fn error_path() {
+    let input = "{
+    server.listeners = [
+        { port = 80 host = \"a\" }
+        { port = 443 host = \"b\" }
+        { port = \"8080\" host = \"c\" }
+    ]
+}";
+
+    let err = from_str::<ServerConfig>(input).unwrap_err();
+    assert!(matches!(
+        &err,
+        Error::DeserializationError { path: Some(path), .. } if path == "server.listeners[2].port"
+    ));
+
+    let diagnostic = err.diagnostic().unwrap();
+    assert_eq!(&input[diagnostic.span.range()], "\"8080\"");
+    assert_eq!(diagnostic.span.start.line, 5);
+}
+
+#[test]
+fn missing_field_path() {
+    let input = "{ server.listeners = [ { port = 80 } ] }";
+
+    let err = from_str::<ServerConfig>(input).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "failed to deserialize input: missing field `host` at `server.listeners[0]`"
+    );
+
+    let diagnostic = err.diagnostic().unwrap();
+    assert_eq!(&input[diagnostic.span.range()], "{ port = 80 }");
+}
+
+#[test]
+fn enum_variant_path() {
+    #[derive(Deserialize, Debug)]
+    #[allow(dead_code)]
+    enum Protocol {
+        Tcp { port: u16 },
+    }
+
+    let input = "{ protocol.Udp.port = 53 }";
+
+    let err = from_str::<HashMap<String, Protocol>>(input).unwrap_err();
+    assert!(matches!(
+        &err,
+        Error::DeserializationError { path: Some(path), .. } if path == "protocol.Udp"
+    ));
+
+    // errors in a variant's name point at the key
+    let diagnostic = err.diagnostic().unwrap();
+    assert_eq!(&input[diagnostic.span.range()], "Udp");
+}
+
+#[test]
+fn enum_variant_wrong_shape() {
+    #[derive(Deserialize, Debug)]
+    #[allow(dead_code)]
+    enum Shape {
+        S { a: u8 },
+        T(u8, u8),
+    }
+
+    let input = "{ e = { S = 1 } }";
+    let err = from_str::<HashMap<String, Shape>>(input).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "failed to deserialize input: expected object for struct variant `S`, found integer at `e.S`"
+    );
+    assert_eq!(&input[err.diagnostic().unwrap().span.range()], "1");
+
+    let input = "{ e = { T = 1 } }";
+    let err = from_str::<HashMap<String, Shape>>(input).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "failed to deserialize input: expected array for tuple variant `T`, found integer at `e.T`"
+    );
+    assert_eq!(&input[err.diagnostic().unwrap().span.range()], "1");
+}
+
+#[test]
+fn quoted_key_path() {
+    let err = from_str::<HashMap<String, HashMap<String, u8>>>("{ 'a.b'.c = true }").unwrap_err();
+    assert!(err.to_string().ends_with("at `'a.b'.c`"));
+}
+
+#[test]
+fn root_error_location() {
+    let err = from_str::<Vec<u8>>("{ foo = 1 }").unwrap_err();
+
+    assert!(matches!(
+        &err,
+        Error::DeserializationError { path: None, .. }
+    ));
+    assert_eq!(err.diagnostic().unwrap().span.start.offset, 0);
+}
+
+#[derive(Deserialize, Debug, PartialEq)]
+struct Port {
+    port: Option<u16>,
+}
+
+#[derive(Deserialize, Debug, PartialEq)]
+struct PortConfig {
+    server: Port,
+    #[serde(default)]
+    backends: Vec<Port>,
+}
+
+#[test]
+fn unknown_keys() {
+    let input = "{
+    server.sever.port = 8080
+    server.prot = 8080
+    backends = [ { port = 80 } { prot = 81 } ]
+}";
+
+    let (config, unknown) = DeserializerOptions::new()
+        .from_str_with_unknown_keys::<PortConfig>(input)
+        .unwrap();
+
+    assert_eq!(config.server, Port { port: None });
+
+    let paths = unknown
+        .iter()
+        .map(|key| key.path.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        paths,
+        vec!["server.sever", "server.prot", "backends[1].prot"]
+    );
+
+    let span = unknown[1].span.unwrap();
+    assert_eq!(&input[span.range()], "prot");
+    assert_eq!(span.start.line, 3);
+}
+
+#[test]
+fn no_unknown_keys() {
+    let (_, unknown) = DeserializerOptions::new()
+        .from_str_with_unknown_keys::<HashMap<String, Port>>("{ server.port = 1 }")
+        .unwrap();
+
+    assert!(unknown.is_empty());
+}
+
+#[test]
+fn deny_unknown_keys() {
+    let input = "{ server.port = 8080 backends = [ { prot = 81 } ] }";
+
+    assert!(from_str::<PortConfig>(input).is_ok());
+
+    let err = DeserializerOptions::new()
+        .unknown_keys(UnknownKeys::Deny)
+        .from_str::<PortConfig>(input)
+        .unwrap_err();
+
+    assert_eq!(
+        err.to_string(),
+        "failed to deserialize input: unknown key at `backends[0].prot`"
+    );
+    assert_eq!(&input[err.diagnostic().unwrap().span.range()], "prot");
+}
+
+#[test]
+fn strict_numbers() {
+    let options = DeserializerOptions::new().int_to_float(false);
+
+    assert!(options
+        .from_str::<Timeouts>("{ connect = 30 read = 5.0 }")
+        .is_err());
+    assert!(options
+        .from_str::<Timeouts>("{ connect = 30.0 read = 5.0 }")
+        .is_ok());
+}
+
+#[test]
+fn strict_enums() {
+    #[derive(Deserialize, Debug, PartialEq)]
+    enum Protocol {
+        Tcp { port: u16 },
+        Udp { port: u16 },
+    }
+
+    let input = "{ protocol = { Tcp.port = 80 Udp.port = 53 } }";
+
+    let config = from_str::<HashMap<String, Protocol>>(input).unwrap();
+    assert_eq!(config["protocol"], Protocol::Tcp { port: 80 });
+
+    let err = DeserializerOptions::new()
+        .strict_enums(true)
+        .from_str::<HashMap<String, Protocol>>(input)
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "failed to deserialize input: expected an object with a single key for an enum variant, found 2 keys at `protocol`"
+    );
+}
+
+#[test]
+fn parse_options() {
+    let options = DeserializerOptions::new().parse_options(
+        ParseOptions::new()
+            .input("$port", Value::from(8080))
+            .strict(true),
+    );
+
+    let config = options
+        .from_str::<HashMap<String, u16>>("{ port = $port }")
+        .unwrap();
+    assert_eq!(config["port"], 8080);
+
+    let err = options
+        .from_str::<HashMap<String, u16>>("{ port = 1 port = 2 }")
+        .unwrap_err();
+    assert!(matches!(err, Error::DuplicateKeyError(..)));
+}
+
+fn deserialize_doubled<'de, D>(deserializer: D) -> Result<u32, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    u32::deserialize(deserializer).map(|value| value * 2)
+}
+
+#[derive(Deserialize, Debug)]
+struct Doubled {
+    #[serde(deserialize_with = "deserialize_doubled")]
+    value: u32,
+}
+
+#[test]
+fn public_deserializer() {
+    let mut deserializer = Deserializer::from_str("{ value = 21 extra = true }").unwrap();
+
+    let config = Doubled::deserialize(&mut deserializer).unwrap();
+    assert_eq!(config.value, 42);
+
+    let unknown = deserializer.unknown_keys();
+    assert_eq!(unknown.len(), 1);
+    assert_eq!(unknown[0].to_string(), "unknown key `extra`");
+}
+
+#[test]
+fn from_value() {
+    let value = corn::parse("{ server.port = 8080 server.host = \"localhost\" }").unwrap();
+
+    let config = corn::from_value_ref::<HashMap<String, Port>>(&value).unwrap();
+    assert_eq!(config["server"], Port { port: Some(8080) });
+
+    let err = corn::from_value::<HashMap<String, HashMap<String, u16>>>(value).unwrap_err();
+    assert!(matches!(
+        &err,
+        Error::DeserializationError { path: Some(path), context: None, .. } if path == "server.host"
+    ));
+}
+
+#[test]
+fn deserialize_value() {
+    #[derive(Deserialize, Debug)]
+    struct Plugin {
+        name: String,
+        settings: Value<'static>,
+    }
+
+    let plugin = from_str::<Plugin>(
+        "{ name = \"cache\" settings = { size = 0xFFFFFFFFFFFFFFFF ratio = 0.5 tags = [ \"a\" null ] } }",
+    )
+    .unwrap();
+
+    assert_eq!(plugin.name, "cache");
+    assert_eq!(
+        serde_json::to_string(&plugin.settings).unwrap(),
+        r#"{"size":18446744073709551615,"ratio":0.5,"tags":["a",null]}"#
+    );
+
+    let value = from_str::<Value>("{ foo = [ 1 true ] }").unwrap();
+    assert!(
+        matches!(value, Value::Object(obj) if matches!(&obj["foo"], Value::Array(arr) if arr.len() == 2))
+    );
+}
+
+#[test]
+fn value_from_other_formats() {
+    let value = serde_json::from_str::<Value>(r#"{"port":8080,"hosts":["a","b"]}"#).unwrap();
+    assert_eq!(
+        corn::to_string(&value).unwrap(),
+        corn::to_string(&corn::parse("{ port = 8080 hosts = [ \"a\" \"b\" ] }").unwrap()).unwrap()
+    );
+}
+
+#[test]
+fn value_into_deserializer() {
+    use serde::de::IntoDeserializer;
+
+    let value = corn::parse("{ port = 8080 }").unwrap();
+
+    let config = Port::deserialize(value.clone().into_deserializer()).unwrap();
+    assert_eq!(config, Port { port: Some(8080) });
+
+    let config = Port::deserialize(value).unwrap();
+    assert_eq!(config, Port { port: Some(8080) });
+}
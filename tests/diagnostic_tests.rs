@@ -0,0 +1,189 @@
+use corn::{parse, parse_all_diagnostics, Diagnostic, ParseOptions, Severity, Value};
+
+fn lines(diagnostics: &[Diagnostic]) -> Vec<usize> {
+    diagnostics
+        .iter()
+        .map(|diagnostic| diagnostic.span.start.line)
+        .collect()
+}
+
+#[test]
+fn valid_input() {
+    let diagnostics = parse_all_diagnostics("let { $foo = 1 } in { foo = $foo }");
+    assert!(diagnostics.is_empty());
+}
+
+#[test]
+fn multiple_syntax_errors() {
+    let input = "{
+    port = = 8080
+    host \"localhost\"
+    name = \"api\"
+    tags = [ \"a\" \"b\" ]
+    retries = 3 3
+}";
+
+    let diagnostics = parse_all_diagnostics(input);
+
+    assert_eq!(lines(&diagnostics), vec![2, 3, 6]);
+    assert!(diagnostics
+        .iter()
+        .all(|diagnostic| diagnostic.severity == Severity::Error));
+}
+
+#[test]
+fn syntax_and_semantic_errors() {
+    let input = "{
+    a = { b = = 1
+        c = $missing }
+    d = [ 1 2 $nope ]
+    e = 1
+    e.f = 2
+}";
+
+    let diagnostics = parse_all_diagnostics(input);
+
+    assert_eq!(lines(&diagnostics), vec![2, 3, 4, 6]);
+    assert_eq!(&input[diagnostics[1].span.range()], "$missing");
+    assert_eq!(&input[diagnostics[2].span.range()], "$nope");
+    assert_eq!(
+        diagnostics[3].message,
+        "attempted to use dot-notation on non-object value at `e.f`"
+    );
+}
+
+#[test]
+fn errors_in_let_block() {
+    let input = "let {
+    $foo = = 1
+    $bar = \"bar\"
+} in {
+    foo = $foo
+    bar = $bar
+    baz = $baz
+}";
+
+    let diagnostics = parse_all_diagnostics(input);
+
+    // `$foo` is reported where it is used, since its definition was skipped
+    assert_eq!(lines(&diagnostics), vec![2, 5, 7]);
+}
+
+#[test]
+fn unclosed_object() {
+    let input = "{\n    a = { b = 1\n    c = $missing\n";
+    let diagnostics = parse_all_diagnostics(input);
+
+    assert!(diagnostics
+        .iter()
+        .all(|diagnostic| diagnostic.span.end.offset <= input.len()));
+
+    assert!(diagnostics
+        .iter()
+        .any(|diagnostic| diagnostic.message.contains("`$missing`")));
+}
+
+#[test]
+fn trailing_input() {
+    let diagnostics = parse_all_diagnostics("{ foo = $foo } bar");
+
+    assert_eq!(diagnostics.len(), 2);
+    assert!(diagnostics[0].message.contains("`$foo`"));
+    assert_eq!(diagnostics[1].span.start.column, 16);
+}
+
+#[test]
+fn unrecoverable() {
+    let diagnostics = parse_all_diagnostics("foo = 1");
+    assert_eq!(diagnostics.len(), 1);
+}
+
+#[test]
+fn options() {
+    let diagnostics = ParseOptions::new()
+        .input("$foo", Value::Integer(1))
+        .parse_all_diagnostics("{ foo = $foo bar = $bar }");
+
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(
+        diagnostics[0].to_string(),
+        "error: failed to resolve referenced input `$bar` at 1:20"
+    );
+}
+
+#[test]
+fn error_diagnostic() {
+    let input = "{\n    port = 8080\n    port.number = 1\n}";
+    let diagnostic = parse(input).unwrap_err().diagnostic().unwrap();
+
+    assert_eq!(&input[diagnostic.span.range()], "port");
+    assert_eq!(diagnostic.span.start.line, 3);
+    assert_eq!(
+        diagnostic.label.as_deref(),
+        Some("cannot set a key inside an integer")
+    );
+
+    assert_eq!(diagnostic.secondary.len(), 1);
+    assert_eq!(&input[diagnostic.secondary[0].span.range()], "8080");
+    assert_eq!(
+        diagnostic.secondary[0].message,
+        "`port` was first set to an integer here"
+    );
+
+    assert_eq!(
+        diagnostic.help.as_deref(),
+        Some("use a different key, or set `port` to an object")
+    );
+}
+
+#[test]
+fn error_diagnostic_spans() {
+    let cases = [
+        ("{ foo = $undefined }", "$undefined"),
+        ("let { $foo = 1 } in { ..$foo }", "$foo"),
+        ("let { $foo = [] } in { foo = \"a $foo\" }", "$foo"),
+        (
+            "let { $foo = \"a\" } in { foo = $foo as integer }",
+            "$foo as integer",
+        ),
+        (
+            "{ foo = $env_CORN_UNSET_VAR!\"required\" }",
+            "$env_CORN_UNSET_VAR!\"required\"",
+        ),
+        ("{ foo = = 1 }", ""),
+    ];
+
+    for (input, expected) in cases {
+        let diagnostic = parse(input).unwrap_err().diagnostic().unwrap();
+        assert_eq!(&input[diagnostic.span.range()], expected, "{input}");
+    }
+}
+
+#[test]
+fn import_error_diagnostic() {
+    let input = "let { $foo = import \"./foo.corn\" } in { foo = $foo }";
+    let diagnostic = parse(input).unwrap_err().diagnostic().unwrap();
+
+    assert_eq!(&input[diagnostic.span.range()], "import \"./foo.corn\"");
+    assert!(diagnostic.help.is_some());
+}
+
+#[test]
+fn expected_rule_descriptions() {
+    let err = parse("{ a = $foo as }").unwrap_err();
+    assert_eq!(
+        err.diagnostic().unwrap().message,
+        "expected cast type (`integer`, `float`, `boolean`, `null`, `array`, `object` or `value`)"
+    );
+
+    let diagnostics = parse_all_diagnostics("let { $foo = } in { a = }");
+    assert!(diagnostics[0]
+        .message
+        .contains("import (`import \"path\"`)"));
+    assert!(diagnostics[1].message.contains("fallback (`??`)"));
+    assert!(diagnostics[1].message.contains("cast (`as`)"));
+
+    assert!(!diagnostics
+        .iter()
+        .any(|diagnostic| diagnostic.message.contains("cast_type")));
+}
@@ -0,0 +1,139 @@
+use corn::error::Error;
+use corn::{parse, parse_all_diagnostics, ParseOptions, Severity, Value};
+
+fn strict_err(input: &str) -> Error {
+    ParseOptions::new().strict(true).parse(input).unwrap_err()
+}
+
+#[test]
+fn duplicate_key() {
+    let input = "{\n    port = 8080\n    port = 9090\n}";
+
+    let config = parse(input).unwrap();
+    assert_eq!(serde_json::to_string(&config).unwrap(), r#"{"port":9090}"#);
+
+    let err = strict_err(input);
+    assert!(matches!(&err, Error::DuplicateKeyError(key, _) if key == "port"));
+
+    let context = err.context().unwrap();
+    assert_eq!(context.span.start.line, 3);
+    assert_eq!(context.secondary[0].span.start.line, 2);
+    assert_eq!(context.secondary[0].message, "first set here");
+}
+
+#[test]
+fn duplicate_warning() {
+    let diagnostics = parse_all_diagnostics("{\n    port = 8080\n    port = 9090\n}");
+
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].severity, Severity::Warning);
+    assert_eq!(diagnostics[0].message, "key `port` is set more than once");
+    assert_eq!(diagnostics[0].span.start.line, 3);
+}
+
+#[test]
+fn duplicate_nested_key() {
+    let err = strict_err("{ foo = { bar = 1 } foo.bar = 2 }");
+    assert!(matches!(err, Error::DuplicateKeyError(key, _) if key == "foo.bar"));
+
+    let err = strict_err("{ foo.bar = 1 foo.bar = 2 }");
+    assert!(matches!(err, Error::DuplicateKeyError(key, _) if key == "foo.bar"));
+}
+
+#[test]
+fn replaced_object() {
+    let input = "{\n    foo.bar = 1\n    foo = 0\n}";
+
+    let config = parse(input).unwrap();
+    assert_eq!(serde_json::to_string(&config).unwrap(), r#"{"foo":0}"#);
+
+    let err = strict_err(input);
+    let context = err.context().unwrap();
+
+    assert_eq!(
+        context.label.as_deref(),
+        Some("replaces the object at `foo`")
+    );
+    assert_eq!(&input[context.secondary[0].span.range()], "foo");
+    assert_eq!(context.secondary[0].span.start.line, 2);
+}
+
+#[test]
+fn merged_keys() {
+    let input = "{ foo.bar = 1 foo.baz = 2 foo = { qux = 3 } qux = 4 }";
+
+    assert!(parse_all_diagnostics(input)
+        .iter()
+        .any(|diagnostic| diagnostic.message == "key `foo` is set more than once"));
+
+    let config = ParseOptions::new()
+        .strict(true)
+        .parse("{ foo.bar = 1 foo.baz = 2 qux = 3 }")
+        .unwrap();
+
+    assert_eq!(
+        serde_json::to_string(&config).unwrap(),
+        r#"{"foo":{"bar":1,"baz":2},"qux":3}"#
+    );
+}
+
+#[test]
+fn spread_override() {
+    let input = "let { $defaults = { port = 8080 host = \"localhost\" } } in {
+        ..$defaults
+        port = 9090
+    }";
+
+    let config = ParseOptions::new().strict(true).parse(input).unwrap();
+    assert_eq!(
+        serde_json::to_string(&config).unwrap(),
+        r#"{"port":9090,"host":"localhost"}"#
+    );
+
+    assert!(parse_all_diagnostics(input).is_empty());
+
+    let err = ParseOptions::new()
+        .strict(true)
+        .input("$defaults", Value::Object(Default::default()))
+        .parse("{ ..$defaults port = 1 port = 2 }")
+        .unwrap_err();
+
+    assert!(matches!(err, Error::DuplicateKeyError(..)));
+}
+
+#[test]
+fn spread_nested_override() {
+    let input = "let { $oo = { a = { b = 1 } } } in { ..$oo a.b = 2 a.b = 3 }";
+
+    let (config, warnings) = corn::parse_with_warnings(input).unwrap();
+    assert_eq!(serde_json::to_string(&config).unwrap(), r#"{"a":{"b":3}}"#);
+
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].message, "key `a.b` is set more than once");
+    assert_eq!(&input[warnings[0].span.range()], "a.b");
+    assert_eq!(warnings[0].span.start.offset, input.rfind("a.b").unwrap());
+
+    let config = ParseOptions::new()
+        .strict(true)
+        .parse("let { $oo = { a = { b = 1 } } } in { ..$oo a.b = 2 a.c = 3 }")
+        .unwrap();
+    assert_eq!(
+        serde_json::to_string(&config).unwrap(),
+        r#"{"a":{"b":2,"c":3}}"#
+    );
+
+    let err = strict_err("let { $oo = { a = { b = 1 } } } in { ..$oo a.c = 2 a = 3 }");
+    assert!(matches!(err, Error::DuplicateKeyError(key, _) if key == "a"));
+}
+
+#[test]
+fn strict_diagnostics() {
+    let diagnostics = ParseOptions::new()
+        .strict(true)
+        .parse_all_diagnostics("{ foo = 1 foo = 2 bar = 1 bar = 2 }");
+
+    assert_eq!(diagnostics.len(), 2);
+    assert!(diagnostics
+        .iter()
+        .all(|diagnostic| diagnostic.severity == Severity::Error));
+}
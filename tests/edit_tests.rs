@@ -0,0 +1,246 @@
+use corn::error::Error;
+use corn::{DocumentMut, Value};
+use paste::paste;
+use std::collections::BTreeMap;
+use std::fs;
+
+macro_rules! generate_round_trip_tests {
+    ($($test_name:ident),+) => {
+        $(
+            paste! {
+                #[test]
+                fn [<round_trip_ $test_name>]() {
+                    let test_name = stringify!($test_name);
+                    let root_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+
+                    let input = fs::read_to_string(format!("{root_dir}/assets/inputs/{test_name}.corn")).unwrap();
+                    let document: DocumentMut = input.parse().unwrap();
+
+                    assert_eq!(document.to_string(), input);
+                }
+            }
+        )+
+    };
+}
+
+generate_round_trip_tests!(
+    basic,
+    chained_complex,
+    comment,
+    compact,
+    complex,
+    readme_example,
+    very_compact
+);
+
+fn edit(input: &str, f: impl FnOnce(&mut DocumentMut)) -> String {
+    let mut document: DocumentMut = input.parse().unwrap();
+    f(&mut document);
+
+    let output = document.to_string();
+    corn::parse(&output).unwrap();
+
+    output
+}
+
+#[test]
+fn get() {
+    let document: DocumentMut =
+        "let { $port = 80 } in { server = { port = $port } a.'b.c' = true }"
+            .parse()
+            .unwrap();
+
+    assert!(matches!(
+        document.get("server.port").unwrap(),
+        Some(Value::Integer(80))
+    ));
+    assert!(matches!(
+        document.get("a.'b.c'").unwrap(),
+        Some(Value::Boolean(true))
+    ));
+    assert!(document.get("server.host").unwrap().is_none());
+    assert!(document.get("a.'b.c'.d").unwrap().is_none());
+}
+
+#[test]
+fn set_existing() {
+    let output = edit(
+        "{\n    // the port\n    port = 80 // trailing\n    host = \"localhost\"\n}",
+        |document| document.set("port", &8080).unwrap(),
+    );
+
+    assert_eq!(
+        output,
+        "{\n    // the port\n    port = 8080 // trailing\n    host = \"localhost\"\n}"
+    );
+}
+
+#[test]
+fn set_inside_object_literal() {
+    let output = edit("{\n  server = {\n    port = 80\n  }\n}", |document| {
+        document.set("server.port", &8080).unwrap();
+        document.set("server.host", "localhost").unwrap();
+    });
+
+    assert_eq!(
+        output,
+        "{\n  server = {\n    port = 8080\n    host = \"localhost\"\n  }\n}"
+    );
+}
+
+#[test]
+fn set_new_path() {
+    let output = edit("{\n    foo = 1\n}", |document| {
+        document.set("bar.'baz.qux'", &true).unwrap();
+    });
+
+    assert_eq!(output, "{\n    foo = 1\n    bar.'baz.qux' = true\n}");
+}
+
+#[test]
+fn set_inline() {
+    assert_eq!(
+        edit("{ foo = 1 }", |document| document.set("bar", &2).unwrap()),
+        "{ foo = 1 bar = 2 }"
+    );
+
+    assert_eq!(
+        edit("{}", |document| document.set("bar", &2).unwrap()),
+        "{ bar = 2 }"
+    );
+}
+
+#[test]
+fn set_object_value() {
+    let value = BTreeMap::from([("a", 1), ("b", 2)]);
+
+    let output = edit("{\n  foo = 1\n}", |document| {
+        document.set("foo", &value).unwrap();
+    });
+
+    assert_eq!(output, "{\n  foo = {\n    a = 1\n    b = 2\n  }\n}");
+}
+
+#[test]
+fn set_replaces_nested_keys() {
+    let output = edit(
+        "{\n    foo.bar = 1\n    foo.baz = 2\n    qux = 3\n}",
+        |document| {
+            document.set("foo", "replaced").unwrap();
+        },
+    );
+
+    assert_eq!(output, "{\n    qux = 3\n    foo = \"replaced\"\n}");
+}
+
+#[test]
+fn set_under_scalar() {
+    let mut document: DocumentMut = "{ foo = 1 }".parse().unwrap();
+
+    assert!(matches!(
+        document.set("foo.bar", &2),
+        Err(Error::InvalidPathError(..))
+    ));
+    assert_eq!(document.to_string(), "{ foo = 1 }");
+}
+
+#[test]
+fn failed_set_leaves_document_unchanged() {
+    let input = "{\n    a = { x.y = 1 }\n    a = 5\n}";
+    let mut document: DocumentMut = input.parse().unwrap();
+
+    assert!(matches!(
+        document.set("a.x", &2),
+        Err(Error::InvalidPathError(..))
+    ));
+    assert_eq!(document.to_string(), input);
+}
+
+#[test]
+fn remove() {
+    let output = edit(
+        "{\n    a = 1 // about a\n    // about b\n    b.c = 2\n    b.d = 3\n}",
+        |document| {
+            assert!(document.remove("a").unwrap());
+            assert!(document.remove("b.c").unwrap());
+            assert!(!document.remove("missing").unwrap());
+        },
+    );
+
+    assert_eq!(output, "{\n    // about b\n    b.d = 3\n}");
+}
+
+#[test]
+fn push() {
+    let output = edit(
+        "{\n  items = [\n    1\n    2\n  ]\n  inline = [ 1 ]\n}",
+        |document| {
+            document.push("items", &3).unwrap();
+            document.push("inline", &2).unwrap();
+        },
+    );
+
+    assert_eq!(
+        output,
+        "{\n  items = [\n    1\n    2\n    3\n  ]\n  inline = [ 1 2 ]\n}"
+    );
+}
+
+#[test]
+fn push_not_array() {
+    let mut document: DocumentMut = "{ foo = 1 }".parse().unwrap();
+
+    assert!(matches!(document.push("foo", &2), Err(Error::EditError(_))));
+    assert!(matches!(document.push("bar", &2), Err(Error::EditError(_))));
+}
+
+#[test]
+fn set_input() {
+    let output = edit(
+        "let {\n    $name = \"foo\"\n} in {\n    name = $name\n}",
+        |document| {
+            document.set_input("$name", "bar").unwrap();
+            document.set_input("$version", "1.0").unwrap();
+        },
+    );
+
+    assert_eq!(
+        output,
+        "let {\n    $name = \"bar\"\n    $version = \"1.0\"\n} in {\n    name = $name\n}"
+    );
+}
+
+#[test]
+fn set_input_without_block() {
+    let output = edit("{\n    name = $name\n}", |document| {
+        document.set_input("$name", "foo").unwrap();
+    });
+
+    assert_eq!(
+        output,
+        "let {\n    $name = \"foo\"\n} in {\n    name = $name\n}"
+    );
+}
+
+#[test]
+fn set_input_invalid_name() {
+    let mut document: DocumentMut = "{}".parse().unwrap();
+
+    assert!(matches!(
+        document.set_input("name", &1),
+        Err(Error::EditError(_))
+    ));
+}
+
+#[test]
+fn remove_input() {
+    let output = edit(
+        "let {\n    $foo = 1\n    $bar = 2\n} in {\n    bar = $bar\n}",
+        |document| {
+            assert!(document.remove_input("$foo"));
+            assert!(!document.remove_input("$baz"));
+        },
+    );
+
+    assert_eq!(output, "let {\n    $bar = 2\n} in {\n    bar = $bar\n}");
+}
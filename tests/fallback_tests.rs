@@ -0,0 +1,105 @@
+use corn::error::Error;
+use corn::resolver::EnvAccess;
+use corn::ParseOptions;
+
+use common::{options, parse};
+
+mod common;
+
+#[test]
+fn defined_input() {
+    assert_eq!(
+        parse("{ level = $env_LOG_LEVEL ?? \"info\" }"),
+        r#"{"level":"debug"}"#
+    );
+}
+
+#[test]
+fn undefined_input() {
+    assert_eq!(
+        parse("{ format = $env_LOG_FORMAT ?? \"json\" }"),
+        r#"{"format":"json"}"#
+    );
+}
+
+#[test]
+fn chained() {
+    assert_eq!(
+        parse("let { $default = \"text\" } in { format = $env_LOG_FORMAT ?? $env_FORMAT ?? $default }"),
+        r#"{"format":"text"}"#
+    );
+}
+
+#[test]
+fn compound_fallback() {
+    assert_eq!(
+        parse("{ tls = $env_TLS ?? { enabled = false } peers = $env_PEERS ?? [ \"localhost\" ] }"),
+        r#"{"tls":{"enabled":false},"peers":["localhost"]}"#
+    );
+}
+
+#[test]
+fn with_cast() {
+    assert_eq!(
+        parse("{ port = $env_PORT as integer ?? 80 workers = $env_WORKERS as integer ?? 4 }"),
+        r#"{"port":8080,"workers":4}"#
+    );
+}
+
+#[test]
+fn in_let_block() {
+    assert_eq!(
+        parse("let { $level = $env_LOG_LEVEL ?? \"info\" } in { level = $level }"),
+        r#"{"level":"debug"}"#
+    );
+}
+
+#[test]
+fn in_array() {
+    assert_eq!(
+        parse("{ values = [ $env_MISSING ?? 1 $env_NAME ?? 2 ] }"),
+        r#"{"values":[1,"api"]}"#
+    );
+}
+
+#[test]
+fn undefined_fallback() {
+    let err = options()
+        .parse("{ format = $env_LOG_FORMAT ?? $undefined }")
+        .unwrap_err();
+
+    assert!(matches!(err, Error::InputResolveError(input, _) if input == "$undefined"));
+}
+
+#[test]
+fn invalid_cast_does_not_fall_back() {
+    let err = options()
+        .parse("{ port = $env_NAME as integer ?? 8080 }")
+        .unwrap_err();
+
+    assert!(matches!(err, Error::InvalidCastError(..)));
+}
+
+#[test]
+fn denied_access_falls_back() {
+    let options = ParseOptions::new().env_access(EnvAccess::None);
+
+    let config = options
+        .parse("{ home = $env_HOME ?? \"/\" port = $env_PORT as integer ?? 80 }")
+        .unwrap();
+    assert_eq!(
+        serde_json::to_string(&config).unwrap(),
+        r#"{"home":"/","port":80}"#
+    );
+
+    let err = options.parse("{ home = $env_HOME }").unwrap_err();
+    assert!(matches!(err, Error::EnvAccessDeniedError(..)));
+}
+
+#[test]
+fn operator_key_still_valid() {
+    let config = corn::parse("let { $foo = 1 } in { a = $foo ?? = 2 }").unwrap();
+    let json = serde_json::to_string(&config).unwrap();
+
+    assert_eq!(json, r#"{"a":1,"??":2}"#);
+}
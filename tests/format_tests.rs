@@ -0,0 +1,164 @@
+use paste::paste;
+use std::fs;
+
+macro_rules! generate_format_tests {
+    ($($test_name:ident),+) => {
+        $(
+            paste! {
+                #[test]
+                fn [<format_ $test_name>]() {
+                    let test_name = stringify!($test_name);
+                    let root_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+
+                    let input = fs::read_to_string(format!("{root_dir}/assets/inputs/{test_name}.corn")).unwrap();
+                    let formatted = corn::format(&input).unwrap();
+
+                    // formatting must not change the meaning of the document
+                    let expected = serde_json::to_value(corn::parse(&input).unwrap()).unwrap();
+                    let actual = serde_json::to_value(corn::parse(&formatted).unwrap()).unwrap();
+                    assert_eq!(actual, expected);
+
+                    // formatting an already formatted document must do nothing
+                    assert_eq!(corn::format(&formatted).unwrap(), formatted);
+                }
+            }
+        )+
+    };
+}
+
+generate_format_tests!(
+    array,
+    basic,
+    basic_empty_let,
+    boolean,
+    chained,
+    chained_complex,
+    char,
+    comment,
+    compact,
+    complex,
+    complex_keys,
+    environment_variable,
+    float,
+    input,
+    input_references_input,
+    integer,
+    mixed_array,
+    null,
+    null_in_array,
+    object,
+    object_in_array,
+    quoted_keys,
+    readme_example,
+    spread,
+    string,
+    string_interpolation,
+    string_multiline,
+    value_after_table,
+    very_compact
+);
+
+#[test]
+fn compact_and_very_compact_match() {
+    let root_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+
+    let compact = fs::read_to_string(format!("{root_dir}/assets/inputs/compact.corn")).unwrap();
+    let very_compact =
+        fs::read_to_string(format!("{root_dir}/assets/inputs/very_compact.corn")).unwrap();
+
+    // the only difference is the blank line in `compact.corn`
+    assert_eq!(
+        corn::format(&compact).unwrap().replace("\n\n", "\n"),
+        corn::format(&very_compact).unwrap()
+    );
+}
+
+#[test]
+fn let_block() {
+    assert_eq!(
+        corn::format("let{$foo=1 $bar={a=1} } in{foo=$foo}").unwrap(),
+        "let {\n    $foo = 1\n    $bar = {\n        a = 1\n    }\n} in {\n    foo = $foo\n}\n"
+    );
+
+    assert_eq!(corn::format("let {   } in {}").unwrap(), "let {} in {}\n");
+}
+
+#[test]
+fn comments() {
+    let input = "// leading\n\n\n{ // after brace\n  a = 1 // trailing\n\n\n\n  // own line\n  b = [ 1 // in array\n  2 ]\n}\n// end";
+
+    assert_eq!(
+        corn::format(input).unwrap(),
+        "// leading\n\n{\n    // after brace\n    a = 1 // trailing\n\n    // own line\n    b = [\n        1 // in array\n        2\n    ]\n}\n// end\n"
+    );
+}
+
+#[test]
+fn comment_after_let_block() {
+    let formatted = corn::format("// top\nlet { $aa = 1 } // after\nin { a = $aa }").unwrap();
+
+    assert_eq!(
+        formatted,
+        "// top\nlet {\n    $aa = 1\n} // after\nin {\n    a = $aa\n}\n"
+    );
+    assert_eq!(corn::format(&formatted).unwrap(), formatted);
+}
+
+#[test]
+fn comment_inside_pair() {
+    let input = "{ a // comment\n = 1 }";
+    let formatted = corn::format(input).unwrap();
+
+    assert_eq!(formatted, "{\n    a // comment\n        = 1\n}\n");
+    assert_eq!(corn::format(&formatted).unwrap(), formatted);
+}
+
+#[test]
+fn arrays() {
+    assert_eq!(
+        corn::format("{ a = [\n1\n2\n..$spread\n] b = [{}] c = [] }").unwrap(),
+        "{\n    a = [ 1 2 ..$spread ]\n    b = [\n        {}\n    ]\n    c = []\n}\n"
+    );
+}
+
+#[test]
+fn invalid_syntax() {
+    assert!(corn::format("{ a = }").is_err());
+}
+
+#[test]
+fn import() {
+    assert_eq!(
+        corn::format("let{$shared=import   \"./shared.corn\"}in{..$shared}").unwrap(),
+        "let {\n    $shared = import \"./shared.corn\"\n} in {\n    ..$shared\n}\n"
+    );
+}
+
+#[test]
+fn cast() {
+    assert_eq!(
+        corn::format("{port=$env_PORT   as   integer}").unwrap(),
+        "{\n    port = $env_PORT as integer\n}\n"
+    );
+}
+
+#[test]
+fn fallback() {
+    assert_eq!(
+        corn::format("{level=$env_LOG_LEVEL??$default   ??\"info\"}").unwrap(),
+        "{\n    level = $env_LOG_LEVEL ?? $default ?? \"info\"\n}\n"
+    );
+}
+
+#[test]
+fn required() {
+    assert_eq!(
+        corn::format("{db=$env_DATABASE_URL!\"set DATABASE_URL\"}").unwrap(),
+        "{\n    db = $env_DATABASE_URL!\"set DATABASE_URL\"\n}\n"
+    );
+
+    assert_eq!(
+        corn::format("let { $aa = 1 } in { a = $aa !\"x\" = 2 }").unwrap(),
+        "let {\n    $aa = 1\n} in {\n    a = $aa\n    !\"x\" = 2\n}\n"
+    );
+}
@@ -0,0 +1,144 @@
+use corn::error::Error;
+use corn::{parse, parse_file, ImportAccess, ParseOptions};
+
+fn asset(name: &str) -> String {
+    let root_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    format!("{root_dir}/assets/imports/{name}")
+}
+
+#[test]
+fn imports() {
+    let config = parse_file(asset("main.corn")).unwrap();
+    let json = serde_json::to_string(&config).unwrap();
+
+    assert_eq!(
+        json,
+        r#"{"service":"api","logging":{"level":"info","format":"json"},"tls":{"enabled":true,"cert":"/etc/tls/cert.pem"}}"#
+    );
+}
+
+#[test]
+fn import_cycle() {
+    let err = parse_file(asset("cycle_a.corn")).unwrap_err();
+
+    let Error::ImportCycleError(cycle, _) = err else {
+        panic!("expected import cycle error, got {err:?}");
+    };
+
+    let files = cycle
+        .split(" -> ")
+        .map(|file| file.rsplit('/').next().unwrap())
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        files,
+        vec!["cycle_a.corn`", "cycle_b.corn`", "cycle_a.corn`"]
+    );
+}
+
+#[test]
+fn self_import() {
+    let err = parse_file(asset("self_import.corn")).unwrap_err();
+    assert!(matches!(err, Error::ImportCycleError(..)));
+}
+
+#[test]
+fn missing_import() {
+    let err = parse_file(asset("missing.corn")).unwrap_err();
+
+    let Error::ImportError(message, _) = err else {
+        panic!("expected import error, got {err:?}");
+    };
+
+    assert!(message.contains("does_not_exist.corn"));
+    assert!(message.contains("imported from"));
+    assert!(message.contains("missing.corn"));
+}
+
+#[test]
+fn error_in_import() {
+    let err = parse_file(asset("nested_error.corn")).unwrap_err();
+
+    let Error::ImportError(message, _) = err else {
+        panic!("expected import error, got {err:?}");
+    };
+
+    assert!(message.contains("invalid.corn"));
+    assert!(message.contains("nested_error.corn"));
+    assert!(message.contains("`$undefined`"));
+}
+
+#[test]
+fn missing_file() {
+    let err = parse_file(asset("does_not_exist.corn")).unwrap_err();
+    assert!(matches!(err, Error::Io(_)));
+}
+
+#[test]
+fn relative_import_without_file() {
+    let err = parse("let { $foo = import \"./foo.corn\" } in { foo = $foo }").unwrap_err();
+    assert!(matches!(err, Error::ImportError(..)));
+}
+
+#[test]
+fn absolute_import_without_file() {
+    let input = format!(
+        "let {{ $tls = import \"{}\" }} in {{ tls = $tls }}",
+        asset("shared/tls.corn")
+    );
+
+    let err = parse(&input).unwrap_err();
+    let Error::ImportError(message, Some(context)) = err else {
+        panic!("expected import error, got {err:?}");
+    };
+
+    assert!(message.contains("without a base file"));
+    assert_eq!(context.label.as_deref(), Some("import outside of a file"));
+}
+
+#[test]
+fn imports_disabled() {
+    let err = ParseOptions::new()
+        .imports(ImportAccess::None)
+        .parse_file(asset("main.corn"))
+        .unwrap_err();
+
+    let Error::ImportError(message, _) = err else {
+        panic!("expected import error, got {err:?}");
+    };
+
+    assert!(message.contains("imports are disabled"));
+}
+
+#[test]
+fn imports_within_directory() {
+    let config = ParseOptions::new()
+        .imports(ImportAccess::Within(asset("shared").into()))
+        .parse_file(asset("main.corn"));
+    assert!(config.is_ok());
+
+    let root_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let err = ParseOptions::new()
+        .imports(ImportAccess::Within(
+            format!("{root_dir}/assets/inputs").into(),
+        ))
+        .parse_file(asset("main.corn"))
+        .unwrap_err();
+
+    let Error::ImportError(message, _) = err else {
+        panic!("expected import error, got {err:?}");
+    };
+
+    assert!(message.contains("outside the allowed directory"));
+}
+
+#[test]
+fn error_in_import_context() {
+    let err = parse_file(asset("nested_error.corn")).unwrap_err();
+
+    let Error::ImportError(_, Some(context)) = err else {
+        panic!("expected import error, got {err:?}");
+    };
+
+    assert_eq!(context.label.as_deref(), Some("imported here"));
+}
@@ -0,0 +1,96 @@
+use corn::error::Error;
+use corn::{parse, parse_all_diagnostics, Value};
+
+fn integer(corn: &str) -> i128 {
+    match parse(corn).unwrap() {
+        Value::Object(obj) => match obj["x"] {
+            Value::Integer(value) => value,
+            ref value => panic!("expected integer, found {value:?}"),
+        },
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+fn integer_bounds() {
+    assert_eq!(integer("{ x = 18446744073709551615 }"), u64::MAX.into());
+    assert_eq!(integer("{ x = 0xFFFFFFFFFFFFFFFF }"), u64::MAX.into());
+    assert_eq!(
+        integer("{ x = 170141183460469231731687303715884105727 }"),
+        i128::MAX
+    );
+    assert_eq!(
+        integer("{ x = -170_141_183_460_469_231_731_687_303_715_884_105_728 }"),
+        i128::MIN
+    );
+    assert_eq!(
+        integer("{ x = 0x7FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF }"),
+        i128::MAX
+    );
+}
+
+#[test]
+fn decimal_overflow() {
+    for input in [
+        "{ x = 170141183460469231731687303715884105728 }",
+        "{ x = -170141183460469231731687303715884105729 }",
+        "{ x = 999999999999999999999999999999999999999999 }",
+    ] {
+        let err = parse(input).unwrap_err();
+        assert!(matches!(err, Error::IntegerOverflowError(..)), "{input}");
+    }
+}
+
+#[test]
+fn hex_overflow() {
+    let input = "{ x = 0xFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF }";
+    let err = parse(input).unwrap_err();
+
+    assert_eq!(
+        err.to_string(),
+        "integer `0xFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF` is out of range"
+    );
+
+    let diagnostic = err.diagnostic().unwrap();
+    assert_eq!(
+        &input[diagnostic.span.range()],
+        "0xFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF"
+    );
+    assert_eq!(
+        diagnostic.label.as_deref(),
+        Some("does not fit in a 128-bit signed integer")
+    );
+}
+
+#[test]
+fn float_overflow() {
+    let err = parse("{ x = 1.0e999 }").unwrap_err();
+    assert!(matches!(err, Error::FloatOverflowError(..)));
+    assert_eq!(err.to_string(), "float `1.0e999` is out of range");
+
+    let err = parse("{ x = -1.0e999 }").unwrap_err();
+    assert!(matches!(err, Error::FloatOverflowError(..)));
+}
+
+#[test]
+fn float_underflow() {
+    let value = parse("{ x = 1.0e-999 }").unwrap();
+    assert!(matches!(value, Value::Object(obj) if matches!(obj["x"], Value::Float(x) if x == 0.0)));
+}
+
+#[test]
+fn overflow_diagnostics() {
+    let input = "{
+    a = 999999999999999999999999999999999999999999
+    b = 1.0e999
+    c = [ 1 0xFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF ]
+}";
+
+    let diagnostics = parse_all_diagnostics(input);
+    let lines = diagnostics
+        .iter()
+        .map(|diagnostic| diagnostic.span.start.line)
+        .collect::<Vec<_>>();
+
+    assert_eq!(lines, vec![2, 3, 4]);
+}
@@ -0,0 +1,112 @@
+use corn::error::Error;
+use corn::{parse_with_inputs, InputPrecedence, Inputs, ParseOptions, SpannedValue, Value};
+
+use common::to_json;
+
+mod common;
+
+#[test]
+fn host_inputs() {
+    let inputs = Inputs::from([
+        ("$region", Value::from("eu-west-1")),
+        ("$build_id", Value::Integer(42)),
+    ]);
+
+    let config = parse_with_inputs("{ region = $region build = $build_id }", inputs).unwrap();
+    assert_eq!(to_json(&config), r#"{"region":"eu-west-1","build":42}"#);
+}
+
+#[test]
+fn host_input_in_let_block() {
+    let config = ParseOptions::new()
+        .input("$region", Value::from("eu-west-1"))
+        .parse("let { $endpoint = \"https://$region.example.com\" } in { endpoint = $endpoint }")
+        .unwrap();
+
+    assert_eq!(
+        to_json(&config),
+        r#"{"endpoint":"https://eu-west-1.example.com"}"#
+    );
+}
+
+#[test]
+fn host_object_input() {
+    let tags = Value::Object(
+        [("team".into(), Value::from("infra"))]
+            .into_iter()
+            .collect(),
+    );
+
+    let config = ParseOptions::new()
+        .input("$tags", tags)
+        .parse("{ tags = { ..$tags env = \"prod\" } }")
+        .unwrap();
+
+    assert_eq!(
+        to_json(&config),
+        r#"{"tags":{"team":"infra","env":"prod"}}"#
+    );
+}
+
+#[test]
+fn host_precedence() {
+    let corn = "let { $region = \"us-east-1\" } in { region = $region }";
+
+    let config = ParseOptions::new()
+        .input("$region", Value::from("eu-west-1"))
+        .parse(corn)
+        .unwrap();
+
+    assert_eq!(to_json(&config), r#"{"region":"eu-west-1"}"#);
+}
+
+#[test]
+fn let_precedence() {
+    let corn = "let { $region = \"us-east-1\" } in { region = $region fallback = $fallback }";
+
+    let config = ParseOptions::new()
+        .input("$region", Value::from("eu-west-1"))
+        .input("$fallback", Value::Boolean(true))
+        .precedence(InputPrecedence::Let)
+        .parse(corn)
+        .unwrap();
+
+    assert_eq!(
+        to_json(&config),
+        r#"{"region":"us-east-1","fallback":true}"#
+    );
+}
+
+#[test]
+fn missing_host_input() {
+    let err = parse_with_inputs("{ region = $region }", Inputs::new()).unwrap_err();
+    assert!(matches!(err, Error::InputResolveError(input, _) if input == "$region"));
+}
+
+#[test]
+fn host_input_span() {
+    let corn = "{ region = $region }";
+
+    let config = ParseOptions::new()
+        .input("$region", Value::from("eu-west-1"))
+        .parse_spanned(corn)
+        .unwrap();
+
+    let SpannedValue::Object(obj) = config.value else {
+        unreachable!()
+    };
+
+    assert_eq!(&corn[obj["region"].value.span.range()], "$region");
+}
+
+#[test]
+fn host_input_in_import() {
+    let root_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+
+    let config = ParseOptions::new()
+        .input("$region", Value::from("eu-west-1"))
+        .parse_file(format!("{root_dir}/assets/imports/host_input.corn"))
+        .unwrap();
+
+    assert_eq!(to_json(&config), r#"{"deployment":{"region":"eu-west-1"}}"#);
+}
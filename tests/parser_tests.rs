@@ -5,6 +5,13 @@ use paste::paste;
 use std::fs;
 
 macro_rules! generate_eq_tests {
+    ($($test_name:ident),+) => {
+        generate_json_yaml_tests!($($test_name),+);
+        generate_toml_tests!($($test_name),+);
+    }
+}
+
+macro_rules! generate_json_yaml_tests {
     ($($test_name:ident),+) => {
         $(
             paste!{
@@ -35,7 +42,15 @@ macro_rules! generate_eq_tests {
 
                     assert_eq!(serialized.trim(), valid.trim());
                 }
+            }
+        )+
+    }
+}
 
+macro_rules! generate_toml_tests {
+    ($($test_name:ident),+) => {
+        $(
+            paste!{
                 #[test]
                 fn [<toml_ $test_name>]() {
                     let test_name = stringify!($test_name);
@@ -104,4 +119,23 @@ generate_eq_tests!(
     very_compact
 );
 
+// TOML only supports 64-bit signed integers
+generate_json_yaml_tests!(large_integer);
+
 generate_invalid_tests!(invalid, invalid_input, invalid_nesting, invalid_spread);
+
+#[test]
+fn toml_large_integer() {
+    let root_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let input = fs::read_to_string(format!("{root_dir}/assets/inputs/large_integer.corn")).unwrap();
+
+    let config = parse(input.as_str()).unwrap();
+    assert!(toml_edit::ser::to_string_pretty(&config).is_err());
+
+    let config = parse("{ max = 9223372036854775807 min = -9223372036854775808 }").unwrap();
+    let serialized = toml_edit::ser::to_string_pretty(&config).unwrap();
+    assert_eq!(
+        serialized.trim(),
+        "max = 9223372036854775807\nmin = -9223372036854775808"
+    );
+}
@@ -0,0 +1,96 @@
+use corn::error::Error;
+use corn::Value;
+
+use common::options;
+
+mod common;
+
+#[test]
+fn set_input() {
+    let config = options()
+        .parse("{ db = $env_DATABASE_URL!\"set DATABASE_URL to the primary DSN\" }")
+        .unwrap();
+
+    assert_eq!(
+        serde_json::to_string(&config).unwrap(),
+        r#"{"db":"postgres://db"}"#
+    );
+}
+
+#[test]
+fn missing_input() {
+    let err = options()
+        .parse("{ db = $env_REPLICA_URL!\"set REPLICA_URL to the replica DSN\" }")
+        .unwrap_err();
+
+    let Error::RequiredInputError { input, message, .. } = &err else {
+        panic!("expected required input error, got {err:?}");
+    };
+
+    assert_eq!(input, "$env_REPLICA_URL");
+    assert_eq!(message, "set REPLICA_URL to the replica DSN");
+    assert_eq!(
+        err.to_string(),
+        "required input `$env_REPLICA_URL` is not set: set REPLICA_URL to the replica DSN"
+    );
+}
+
+#[test]
+fn let_block_is_ignored() {
+    let err = options()
+        .parse("let { $env_REPLICA_URL = \"default\" } in { db = $env_REPLICA_URL!\"must come from the environment\" }")
+        .unwrap_err();
+
+    assert!(matches!(err, Error::RequiredInputError { .. }));
+}
+
+#[test]
+fn host_input() {
+    let config = options()
+        .input("$region", Value::from("eu-west-1"))
+        .parse("{ region = $region!\"the deploy tool must set a region\" }")
+        .unwrap();
+
+    assert_eq!(
+        serde_json::to_string(&config).unwrap(),
+        r#"{"region":"eu-west-1"}"#
+    );
+}
+
+#[test]
+fn with_cast() {
+    let config = options()
+        .parse("{ port = $env_PORT!\"set PORT\" as integer }")
+        .unwrap();
+
+    assert_eq!(serde_json::to_string(&config).unwrap(), r#"{"port":8080}"#);
+
+    let err = options()
+        .parse("{ workers = $env_WORKERS!\"set WORKERS\" as integer }")
+        .unwrap_err();
+
+    assert!(matches!(err, Error::RequiredInputError { input, .. } if input == "$env_WORKERS"));
+}
+
+#[test]
+fn interpolated_message() {
+    let err = options()
+        .parse(
+            "let { $docs = \"https://example.com/docs\" } in { key = $env_API_KEY!\"see $docs\" }",
+        )
+        .unwrap_err();
+
+    assert!(
+        matches!(err, Error::RequiredInputError { message, .. } if message == "see https://example.com/docs")
+    );
+}
+
+#[test]
+fn key_after_input() {
+    let config = corn::parse("let { $aa = 1 } in { a = $aa !\"x\" = 2 }").unwrap();
+
+    assert_eq!(
+        serde_json::to_string(&config).unwrap(),
+        r#"{"a":1,"!\"x\"":2}"#
+    );
+}
@@ -0,0 +1,162 @@
+use corn::error::{Error, Result};
+use corn::resolver::{DenyResolver, EnvAccess, MapResolver};
+use corn::{InputResolver, ParseOptions, Value};
+
+use common::to_json;
+
+mod common;
+
+#[test]
+fn map_resolver() {
+    let config = ParseOptions::new()
+        .resolver(
+            "$secret_",
+            MapResolver::from_iter([("DB_PASSWORD", "hunter2")]),
+        )
+        .parse("{ password = $secret_DB_PASSWORD }")
+        .unwrap();
+
+    assert_eq!(to_json(&config), r#"{"password":"hunter2"}"#);
+}
+
+#[test]
+fn replace_env_resolver() {
+    let config = ParseOptions::new()
+        .resolver("$env_", MapResolver::from_iter([("HOME", "/home/corn")]))
+        .parse("{ home = $env_HOME }")
+        .unwrap();
+
+    assert_eq!(to_json(&config), r#"{"home":"/home/corn"}"#);
+}
+
+#[test]
+fn deny_resolver_falls_back() {
+    let corn = "let { $env_PATH = \"default\" } in { path = $env_PATH }";
+
+    let config = ParseOptions::new()
+        .resolver("$env_", DenyResolver)
+        .parse(corn)
+        .unwrap();
+
+    assert_eq!(to_json(&config), r#"{"path":"default"}"#);
+}
+
+#[test]
+fn deny_resolver_unresolved() {
+    let err = ParseOptions::new()
+        .resolver("$env_", DenyResolver)
+        .parse("{ path = $env_PATH }")
+        .unwrap_err();
+
+    assert!(matches!(err, Error::InputResolveError(input, _) if input == "$env_PATH"));
+}
+
+#[test]
+fn longest_prefix() {
+    let config = ParseOptions::new()
+        .resolver("$vault_", MapResolver::from_iter([("prod_key", "general")]))
+        .resolver(
+            "$vault_prod_",
+            MapResolver::from_iter([("key", "specific")]),
+        )
+        .parse("{ key = $vault_prod_key }")
+        .unwrap();
+
+    assert_eq!(to_json(&config), r#"{"key":"specific"}"#);
+}
+
+#[test]
+fn non_string_values() {
+    let config = ParseOptions::new()
+        .resolver(
+            "$flag_",
+            MapResolver::from_iter([("enabled", Value::Boolean(true))]),
+        )
+        .parse("{ enabled = $flag_enabled }")
+        .unwrap();
+
+    assert_eq!(to_json(&config), r#"{"enabled":true}"#);
+}
+
+struct FailingResolver;
+
+impl InputResolver for FailingResolver {
+    fn resolve(&self, name: &str) -> Result<Option<Value<'static>>> {
+        Err(Error::InputResolveError(
+            format!("vault unavailable for `{name}`"),
+            None,
+        ))
+    }
+}
+
+#[test]
+fn resolver_error() {
+    let err = ParseOptions::new()
+        .resolver("$vault_", FailingResolver)
+        .parse("{ key = $vault_key }")
+        .unwrap_err();
+
+    assert!(matches!(&err, Error::InputResolveError(message, _) if message.contains("`key`")));
+
+    // the location of the reference is attached to errors from resolvers
+    let context = err.context().unwrap();
+    assert_eq!(context.span.start.column, 9);
+    assert_eq!(context.label.as_deref(), Some("failed to resolve"));
+}
+
+#[test]
+fn env_access_none() {
+    let err = ParseOptions::new()
+        .env_access(EnvAccess::None)
+        .parse("{ test = $env_CORN_TEST }")
+        .unwrap_err();
+
+    assert!(matches!(err, Error::EnvAccessDeniedError(name, _) if name == "CORN_TEST"));
+}
+
+#[test]
+fn env_access_denied_in_string() {
+    let err = ParseOptions::new()
+        .env_access(EnvAccess::None)
+        .parse("{ url = \"https://$env_CORN_TEST.example.com\" }")
+        .unwrap_err();
+
+    assert!(matches!(err, Error::EnvAccessDeniedError(..)));
+}
+
+#[test]
+fn env_access_allowlist() {
+    let options = ParseOptions::new().env_access(EnvAccess::allowlist(["CORN_ALLOWED"]));
+
+    let err = options
+        .parse("{ secret = $env_AWS_SECRET_ACCESS_KEY }")
+        .unwrap_err();
+    assert!(matches!(err, Error::EnvAccessDeniedError(name, _) if name == "AWS_SECRET_ACCESS_KEY"));
+
+    // allowed variables which are not set still fall back to the let block
+    let config = options
+        .parse("let { $env_CORN_ALLOWED = \"default\" } in { allowed = $env_CORN_ALLOWED }")
+        .unwrap();
+    assert_eq!(to_json(&config), r#"{"allowed":"default"}"#);
+}
+
+#[test]
+fn env_access_prefix() {
+    let options = ParseOptions::new().env_access(EnvAccess::Prefix(String::from("CORN_")));
+
+    let config = options
+        .parse("let { $env_CORN_UNSET_VARIABLE = 1 } in { value = $env_CORN_UNSET_VARIABLE }")
+        .unwrap();
+    assert_eq!(to_json(&config), r#"{"value":1}"#);
+
+    let err = options.parse("{ home = $env_HOME }").unwrap_err();
+    assert!(matches!(err, Error::EnvAccessDeniedError(name, _) if name == "HOME"));
+}
+
+#[test]
+fn options_are_thread_safe() {
+    fn assert_send_sync<T: Send + Sync>() {}
+
+    assert_send_sync::<ParseOptions>();
+    assert_send_sync::<corn::DeserializerOptions>();
+}
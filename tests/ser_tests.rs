@@ -0,0 +1,286 @@
+use corn::{parse, to_string, to_string_pretty, to_value, Serializer, Value};
+use paste::paste;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+
+macro_rules! generate_round_trip_tests {
+    ($($test_name:ident),+) => {
+        $(
+            paste! {
+                #[test]
+                fn [<round_trip_ $test_name>]() {
+                    let test_name = stringify!($test_name);
+                    let root_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+
+                    let input = fs::read_to_string(format!("{root_dir}/assets/inputs/{test_name}.corn")).unwrap();
+                    let config = parse(&input).unwrap();
+                    let expected = serde_json::to_string(&config).unwrap();
+
+                    let compact = to_string(&config).unwrap();
+                    let compact = serde_json::to_string(&parse(&compact).unwrap()).unwrap();
+                    assert_eq!(compact, expected);
+
+                    let pretty = to_string_pretty(&config).unwrap();
+                    let pretty = serde_json::to_string(&parse(&pretty).unwrap()).unwrap();
+                    assert_eq!(pretty, expected);
+                }
+            }
+        )+
+    };
+}
+
+generate_round_trip_tests!(
+    array,
+    basic,
+    basic_empty_let,
+    boolean,
+    chained,
+    chained_complex,
+    char,
+    comment,
+    compact,
+    complex,
+    complex_keys,
+    float,
+    input,
+    input_references_input,
+    integer,
+    mixed_array,
+    null,
+    null_in_array,
+    object,
+    object_in_array,
+    quoted_keys,
+    readme_example,
+    spread,
+    string,
+    string_interpolation,
+    string_multiline,
+    value_after_table,
+    very_compact
+);
+
+#[derive(Serialize)]
+struct Config {
+    name: String,
+    port: u16,
+    ratio: f32,
+    tags: Vec<&'static str>,
+    server: Server,
+    mode: Mode,
+    fallback: Option<u8>,
+}
+
+#[derive(Serialize)]
+struct Server {
+    tls: Tls,
+    hosts: Vec<Host>,
+}
+
+#[derive(Serialize)]
+struct Tls {
+    enabled: bool,
+}
+
+#[derive(Serialize)]
+struct Host {
+    name: &'static str,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "snake_case")]
+enum Mode {
+    Release,
+}
+
+fn config() -> Config {
+    Config {
+        name: String::from("corn"),
+        port: 8080,
+        ratio: 1.0,
+        tags: vec!["a", "b"],
+        server: Server {
+            tls: Tls { enabled: true },
+            hosts: vec![Host { name: "localhost" }],
+        },
+        mode: Mode::Release,
+        fallback: None,
+    }
+}
+
+#[test]
+fn struct_compact() {
+    let corn = to_string(&config()).unwrap();
+
+    assert_eq!(
+        corn,
+        "{ name = \"corn\" port = 8080 ratio = 1.0 tags = [ \"a\" \"b\" ] \
+         server = { tls = { enabled = true } hosts = [ { name = \"localhost\" } ] } \
+         mode = \"release\" fallback = null }"
+    );
+}
+
+#[test]
+fn struct_pretty() {
+    let corn = to_string_pretty(&config()).unwrap();
+
+    assert_eq!(
+        corn,
+        r#"{
+    name = "corn"
+    port = 8080
+    ratio = 1.0
+    tags = [ "a" "b" ]
+    server = {
+        tls = {
+            enabled = true
+        }
+        hosts = [
+            {
+                name = "localhost"
+            }
+        ]
+    }
+    mode = "release"
+    fallback = null
+}"#
+    );
+}
+
+#[test]
+fn collapse_paths() {
+    let mut serializer = Serializer::pretty().collapse_paths(true);
+    config().serialize(&mut serializer).unwrap();
+
+    let corn = serializer.into_inner();
+    assert!(corn.contains("\n    server = {\n        tls.enabled = true\n"));
+
+    let value = serde_json::to_value(parse(&corn).unwrap()).unwrap();
+    assert_eq!(value, serde_json::to_value(config()).unwrap());
+}
+
+#[test]
+fn quoted_keys() {
+    let mut map = HashMap::new();
+    map.insert("with space", 1);
+
+    assert_eq!(to_string(&map).unwrap(), "{ 'with space' = 1 }");
+
+    let mut map = HashMap::new();
+    map.insert("it's.dotted", 1);
+
+    let corn = to_string(&map).unwrap();
+    assert_eq!(corn, "{ 'it\\'s.dotted' = 1 }");
+    assert_eq!(
+        serde_json::to_string(&parse(&corn).unwrap()).unwrap(),
+        "{\"it's.dotted\":1}"
+    );
+}
+
+#[test]
+fn escaped_strings() {
+    let mut map = HashMap::new();
+    map.insert("foo", "\"quoted\" \\ $input $5 \t \u{7}");
+
+    let corn = to_string(&map).unwrap();
+    assert_eq!(
+        corn,
+        "{ foo = \"\\\"quoted\\\" \\\\ \\$input $5 \\t \\u0007\" }"
+    );
+
+    let parsed = parse(&corn).unwrap();
+    assert_eq!(
+        serde_json::to_value(parsed).unwrap()["foo"],
+        "\"quoted\" \\ $input $5 \t \u{7}"
+    );
+}
+
+#[test]
+fn multiline_strings() {
+    for string in [
+        "hello\nworld",
+        "hello\n  indented\nworld",
+        "\nleading newline",
+        "trailing newline\n",
+        "  both\n  indented\n",
+        "blank\n\nline",
+    ] {
+        let mut map = HashMap::new();
+        map.insert("foo", string);
+
+        for corn in [to_string(&map).unwrap(), to_string_pretty(&map).unwrap()] {
+            let parsed = parse(&corn).unwrap();
+            assert_eq!(serde_json::to_value(parsed).unwrap()["foo"], string);
+        }
+    }
+}
+
+#[test]
+fn floats() {
+    let mut map = HashMap::new();
+    map.insert("foo", 1e100);
+
+    let corn = to_string(&map).unwrap();
+    assert_eq!(corn, "{ foo = 1.0e100 }");
+    assert_eq!(
+        serde_json::to_value(parse(&corn).unwrap()).unwrap()["foo"],
+        1e100
+    );
+}
+
+#[test]
+fn invalid_values() {
+    assert!(to_string(&42).is_err());
+    assert!(to_string(&vec![1, 2]).is_err());
+
+    let mut map = HashMap::new();
+    map.insert("foo", f64::NAN);
+    assert!(to_string(&map).is_err());
+
+    let mut map = HashMap::new();
+    map.insert("foo", "  only\n  indented");
+    assert!(to_string(&map).is_err());
+
+    let mut map = HashMap::new();
+    map.insert("", 1);
+    assert!(to_string(&map).is_err());
+}
+
+#[test]
+fn struct_to_value() {
+    let value = to_value(&config()).unwrap();
+
+    let corn = to_string(&config()).unwrap();
+    let expected = parse(&corn).unwrap();
+
+    assert_eq!(
+        serde_json::to_string(&value).unwrap(),
+        serde_json::to_string(&expected).unwrap()
+    );
+
+    let ports = HashMap::from([("http", 80), ("https", 443)]);
+    let value = to_value(&ports).unwrap();
+    assert_eq!(
+        corn::from_value::<HashMap<String, u16>>(value).unwrap()["https"],
+        443
+    );
+}
+
+#[test]
+fn scalar_to_value() {
+    assert!(matches!(to_value(&42).unwrap(), Value::Integer(42)));
+    assert!(matches!(to_value(&u64::MAX).unwrap(), Value::Integer(i) if i == i128::from(u64::MAX)));
+    assert!(matches!(to_value("foo").unwrap(), Value::String(s) if s == "foo"));
+    assert!(matches!(to_value(&None::<u8>).unwrap(), Value::Null(None)));
+    assert!(matches!(to_value(&vec![1, 2]).unwrap(), Value::Array(arr) if arr.len() == 2));
+}
+
+#[test]
+fn invalid_to_value() {
+    assert!(to_value(&u128::MAX).is_err());
+
+    let mut map = HashMap::new();
+    map.insert(vec![1], 1);
+    assert!(to_value(&map).is_err());
+}
@@ -0,0 +1,84 @@
+use corn::{parse_spanned, Spanned, SpannedValue};
+
+fn get<'a>(value: &'a Spanned<SpannedValue<'a>>, key: &str) -> &'a corn::SpannedEntry<'a> {
+    match &value.value {
+        SpannedValue::Object(obj) => &obj[key],
+        _ => panic!("expected object"),
+    }
+}
+
+#[test]
+fn root_span() {
+    let input = "{ foo = 1 }";
+    let config = parse_spanned(input).unwrap();
+
+    assert_eq!(config.span.range(), 0..input.len());
+}
+
+#[test]
+fn pair_spans() {
+    let input = "{\n    foo = \"bar\"\n    baz = [ 1 2 ]\n}";
+    let config = parse_spanned(input).unwrap();
+
+    let foo = get(&config, "foo");
+    assert_eq!(&input[foo.key.range()], "foo");
+    assert_eq!(&input[foo.value.span.range()], "\"bar\"");
+    assert_eq!((foo.key.start.line, foo.key.start.column), (2, 5));
+
+    let baz = get(&config, "baz");
+    assert_eq!(
+        (baz.value.span.start.line, baz.value.span.start.column),
+        (3, 11)
+    );
+
+    let SpannedValue::Array(arr) = &baz.value.value else {
+        panic!("expected array")
+    };
+    assert_eq!(&input[arr[1].span.range()], "2");
+}
+
+#[test]
+fn chained_key_spans() {
+    let input = "{ foo.bar = 1 }";
+    let config = parse_spanned(input).unwrap();
+
+    let foo = get(&config, "foo");
+    assert_eq!(&input[foo.key.range()], "foo");
+    assert_eq!(&input[foo.value.span.range()], "foo.bar = 1");
+
+    let bar = get(&foo.value, "bar");
+    assert_eq!(&input[bar.key.range()], "bar");
+    assert_eq!(&input[bar.value.span.range()], "1");
+}
+
+#[test]
+fn input_reference_spans() {
+    let input = "let { $foo = { bar = 1 } } in { baz = $foo }";
+    let config = parse_spanned(input).unwrap();
+
+    let baz = get(&config, "baz");
+    assert_eq!(&input[baz.value.span.range()], "$foo");
+
+    let bar = get(&baz.value, "bar");
+    assert_eq!(&input[bar.value.span.range()], "1");
+    assert!(bar.value.span.start.offset < baz.value.span.start.offset);
+}
+
+#[test]
+fn unicode_columns() {
+    let input = "{ with_🌽 = 2 }";
+    let config = parse_spanned(input).unwrap();
+
+    let entry = get(&config, "with_🌽");
+    assert_eq!(entry.value.span.start.column, 12);
+}
+
+#[test]
+fn into_value() {
+    let input = "{ foo.bar = [ 1 \"two\" null ] }";
+
+    let spanned = serde_json::to_string(&corn::Value::from(parse_spanned(input).unwrap()));
+    let plain = serde_json::to_string(&corn::parse(input).unwrap());
+
+    assert_eq!(spanned.unwrap(), plain.unwrap());
+}
@@ -0,0 +1,142 @@
+use corn::resolver::MapResolver;
+use corn::{parse_all_diagnostics, parse_with_warnings, ParseOptions, Severity, Value};
+
+#[test]
+fn no_warnings() {
+    let (_, warnings) =
+        parse_with_warnings("let { $foo = 1 $bar = [ $foo ] } in { bar = $bar }").unwrap();
+    assert!(warnings.is_empty());
+}
+
+#[test]
+fn unused_input() {
+    let input = "let {\n    $used = 1\n    $stale = 2\n} in { foo = $used }";
+    let (config, warnings) = parse_with_warnings(input).unwrap();
+
+    assert_eq!(serde_json::to_string(&config).unwrap(), r#"{"foo":1}"#);
+
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].severity, Severity::Warning);
+    assert_eq!(warnings[0].message, "input `$stale` is never used");
+    assert_eq!(&input[warnings[0].span.range()], "$stale");
+}
+
+#[test]
+fn input_used_by_fallback_and_cast() {
+    let input = "let { $foo = \"1\" $bar = 2 } in { foo = $foo as integer bar = $baz ?? $bar }";
+    let (_, warnings) = parse_with_warnings(input).unwrap();
+
+    assert!(warnings.is_empty());
+}
+
+#[test]
+fn input_in_unevaluated_fallback() {
+    let input = "let { $default = \"x\" } in { a = $env_HOME ?? $default }";
+    let options =
+        ParseOptions::new().resolver("$env_", MapResolver::from_iter([("HOME", "/root")]));
+
+    let (config, warnings) = options.parse_with_warnings(input).unwrap();
+    assert_eq!(serde_json::to_string(&config).unwrap(), r#"{"a":"/root"}"#);
+    assert!(warnings.is_empty());
+
+    assert!(options.parse_all_diagnostics(input).is_empty());
+}
+
+#[test]
+fn input_overridden_by_host() {
+    let (_, warnings) = ParseOptions::new()
+        .input("$foo", Value::Integer(2))
+        .parse_with_warnings("let { $foo = 1 } in { foo = $foo }")
+        .unwrap();
+
+    assert!(warnings.is_empty());
+}
+
+#[test]
+fn shadowed_env_input() {
+    let input = "let { $env_CORN_TEST = \"foo\" } in { foo = $env_CORN_TEST }";
+    let (config, warnings) = ParseOptions::new()
+        .resolver("$env_", MapResolver::from_iter([("CORN_TEST", "bar")]))
+        .parse_with_warnings(input)
+        .unwrap();
+
+    assert_eq!(serde_json::to_string(&config).unwrap(), r#"{"foo":"bar"}"#);
+
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(
+        warnings[0].message,
+        "environment variable `CORN_TEST` overrides input `$env_CORN_TEST` in the `let` block"
+    );
+    assert_eq!(
+        &input[warnings[0].secondary[0].span.range()],
+        "$env_CORN_TEST"
+    );
+}
+
+#[test]
+fn unset_env_input() {
+    let input = "let { $env_CORN_UNSET_VAR = \"foo\" } in { foo = $env_CORN_UNSET_VAR }";
+    let (_, warnings) = parse_with_warnings(input).unwrap();
+
+    assert!(warnings.is_empty());
+}
+
+#[test]
+fn empty_spreads() {
+    let input = "let { $obj = {} $arr = [] } in { ..$obj arr = [ ..$arr 1 ] }";
+    let (config, warnings) = parse_with_warnings(input).unwrap();
+
+    assert_eq!(serde_json::to_string(&config).unwrap(), r#"{"arr":[1]}"#);
+
+    let messages = warnings
+        .iter()
+        .map(|warning| warning.message.as_str())
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        messages,
+        vec![
+            "spread of empty object `$obj` has no effect",
+            "spread of empty array `$arr` has no effect"
+        ]
+    );
+    assert_eq!(&input[warnings[0].span.range()], "..$obj");
+}
+
+#[test]
+fn warnings_in_diagnostics() {
+    let diagnostics = parse_all_diagnostics("let { $foo = 1 } in { bar = $bar }");
+
+    assert_eq!(diagnostics.len(), 2);
+    assert_eq!(diagnostics[0].severity, Severity::Warning);
+    assert_eq!(diagnostics[1].severity, Severity::Error);
+}
+
+#[test]
+fn input_used_by_unused_input() {
+    let input = "let { $aa = 1 $bb = [ $aa ] } in { c = 1 }";
+    let (_, warnings) = parse_with_warnings(input).unwrap();
+
+    let messages = warnings
+        .iter()
+        .map(|warning| warning.message.as_str())
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        messages,
+        vec!["input `$aa` is never used", "input `$bb` is never used"]
+    );
+}
+
+#[test]
+fn duplicate_input() {
+    let input = "let { $aa = 1 $aa = 2 } in { c = $aa }";
+    let (config, warnings) = parse_with_warnings(input).unwrap();
+
+    assert_eq!(serde_json::to_string(&config).unwrap(), r#"{"c":2}"#);
+
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].message, "input `$aa` is defined more than once");
+    assert_eq!(warnings[0].span.start.offset, input.rfind("$aa =").unwrap());
+    assert_eq!(warnings[0].secondary[0].span.start.offset, 6);
+}